@@ -8,18 +8,60 @@ const VERSION: &str = env!("CARGO_PKG_VERSION");
 #[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
 pub enum Event {
     AddBlob,
+    BeginBlobUpload,
+    CancelBlobUpload,
     AddReleaseInfo,
     AddDeploymentInfo,
     UpdateDeploymentInfo,
+    DeploymentFailure,
     AttachFullAccessKey,
     Deploy,
     DelegatedPause,
     DelegatedExecution,
     SetLatestReleaseInfo,
+    SetChannelRelease,
+    RemoveChannel,
+    SetDeploymentChannel,
     RemoveReleaseInfo,
+    RefundStorageStake,
+    SetStorageTreasury,
     Upgrade,
+    BatchUpgrade,
+    BatchUpgradeCompleted,
+    CreateRollout,
+    AdvanceRollout,
+    RollbackRolloutWave,
     UnrestrictedUpgrade,
+    SetUpgradeDelay,
+    ScheduleUpgrade,
+    CancelScheduledUpgrade,
     Downgrade,
+    SetProposalPolicy,
+    CreateProposal,
+    ApproveProposal,
+    ExecuteProposal,
+    RemoveProposal,
+    SetFamilyUpgradeMethod,
+    SetDeploymentUpgradeMethod,
+    SetUpgradePolicy,
+    UpgradePolicyViolation,
+    CodeHashDrift,
+    SetDefaultAccessKeys,
+    SetAccountCreator,
+    SetRefundBeneficiary,
+    RefundDeployDeposit,
+    RemoveFailedDeployment,
+    DelegatedUnpause,
+    SetFamilyPauseMethods,
+    SetDeploymentPauseMethods,
+    FleetPause,
+    FleetPauseCompleted,
+    SetFamilyDelegationPolicy,
+    SetDeploymentDelegationPolicy,
+    DelegatedExecutionPlan,
+    ExecutionPlanCompleted,
+    DelegatedExecutionCompleted,
+    SetProductionFlag,
 }
 
 #[derive(Serialize)]
@@ -79,6 +121,7 @@ fn test_stringify_event_metadata() {
         is_blob_exist: false,
         downgrade_hash: None,
         description: Some("Aurora SILO 3.5.0".to_string()),
+        family: None,
     };
     let event_metadata = EventMetadata::new(Event::AddReleaseInfo, &release_info);
     let version = env!("CARGO_PKG_VERSION");
@@ -8,4 +8,29 @@ pub enum Prefix {
     Deployments,
     Releases,
     LatestRelease,
+    BlobUploads,
+    BlobChunks,
+    StorageStakes,
+    BatchUpgrades,
+    Rollouts,
+    UpgradeDelays,
+    ScheduledUpgrades,
+    Proposals,
+    Channels,
+    FamilyUpgradeMethods,
+    DeploymentUpgradeMethods,
+    FamilyUpgradePolicies,
+    ReleaseUpgradePolicies,
+    CodeDrifts,
+    FailedDeployments,
+    FamilyPauseMethods,
+    DeploymentPauseMethods,
+    FleetPauses,
+    DeploymentFleetPauses,
+    FamilyDelegationPolicies,
+    DeploymentDelegationPolicies,
+    Executions,
+    FamilyLatestReleases,
+    FamilyProductionFlags,
+    DeploymentProductionFlags,
 }
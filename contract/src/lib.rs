@@ -1,24 +1,34 @@
 use near_plugins::{
     access_control, access_control_any, AccessControlRole, AccessControllable, Pausable, Upgradable,
 };
-use near_sdk::borsh::BorshDeserialize;
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
 use near_sdk::collections::LazyOption;
+use near_sdk::json_types::{Base64VecU8, U128};
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::serde_json::{json, Value};
-use near_sdk::store::IterableMap;
+use near_sdk::store::{IterableMap, LookupMap};
 use near_sdk::{
-    assert_one_yocto, env, ext_contract, near, require, AccountId, Gas, NearToken, PanicOnDefault,
-    Promise, PromiseResult, PublicKey,
+    assert_one_yocto, env, ext_contract, near, require, AccountId, Allowance, Gas, GasWeight,
+    NearToken, PanicOnDefault, Promise, PromiseOrValue, PromiseResult, PublicKey,
 };
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use crate::event::Event;
 use crate::types::{
-    DeploymentInfo, FunctionCallArgs, LogFunctionCallArgs, ReleaseInfo, UpgradeArgs, Version,
+    family_name, logged_bytes, AccessKeyInfo, BatchUpgrade, BlobUpload, CodeDrift,
+    DelegationPolicy, DeployOptions, DeployTarget, DeploymentAction, DeploymentError,
+    DeploymentFilter, DeploymentInfo, DeploymentPlan, DepositSplit, ExecutionMode, ExecutionRecord,
+    ExecutionStatus, ExecutionStep, ExecutionStepResult, ExecutionStepStatus, FailedDeployment,
+    FleetPause, FunctionCallArgs, KeyPermission, KeyPolicy, LogFunctionCallArgs, PauseMethods,
+    PauseStatus, PolicyViolation, Preflight, Proposal, ProposalAction, ProposalPolicy,
+    ReleaseFilter, ReleaseInfo, ReleaseReferences, ReleaseStorageStake, Rollout, RolloutWave,
+    ScheduledUpgrade, StorageStake, UpgradeArgs, UpgradeArgsEncoding, UpgradeMethod, UpgradePolicy,
+    UpgradeStatus, Version, WaveSelection,
 };
 
 mod event;
 mod keys;
+mod migration;
 #[cfg(test)]
 mod tests;
 pub mod types;
@@ -33,15 +43,67 @@ const UPGRADE_GAS: Gas = Gas::from_tgas(130);
 /// Gas needed to upgrade contract (except a gas for the migration state).
 const UPGRADE_GAS_NO_MIGRATION_GAS: Gas = Gas::from_tgas(180);
 
+/// Gas needed to create the account by the account creator contract.
+const CREATE_ACCOUNT_GAS: Gas = Gas::from_tgas(80);
+
+/// Gas needed to stage the code by the `up_stage_code` method of `near-plugins`.
+const STAGE_CODE_GAS: Gas = Gas::from_tgas(70);
+
 /// Gas needed to call the `add_deployment` callback.
 const ADD_DEPLOYMENT_GAS: Gas = Gas::from_tgas(5);
 
+/// Gas needed to call the `finish_deploy` callback which could refund the deposit.
+const FINISH_DEPLOY_GAS: Gas = Gas::from_tgas(10);
+
+/// Gas needed to call the `refund_returned_deposit` callback.
+const REFUND_DEPOSIT_GAS: Gas = Gas::from_tgas(5);
+
+/// Gas needed to call the `finish_account_creation` callback without taking into account the gas
+/// attached to the init call and the `finish_deploy` callback.
+const FINISH_ACCOUNT_CREATION_GAS: Gas = Gas::from_tgas(10);
+
+/// Amount of gas used by `batch_upgrade` to schedule the upgrade of one contract without taking
+/// into account the gas attached to the promises.
+const BATCH_UPGRADE_TARGET_GAS: Gas = Gas::from_tgas(10);
+
+/// Amount of gas reserved in `batch_upgrade` for storing the state of the batch.
+const BATCH_UPGRADE_RESERVED_GAS: Gas = Gas::from_tgas(15);
+
 /// Amount of gas used by `delegate_pause` in the controller contract
 /// without taking into account the gas consumed by the promise.
 const OUTER_DELEGATE_PAUSE_GAS: Gas = Gas::from_tgas(10);
 
-/// Allowed pause methods.
-const ALLOWED_PAUSE_METHODS: &[&str] = &["pause_contract", "pa_pause_feature"];
+/// Gas attached to the pause method of each contract by `pause_all` if the gas isn't set in
+/// the pause methods.
+const FLEET_PAUSE_GAS: Gas = Gas::from_tgas(10);
+
+/// Gas needed to call the `finish_fleet_pause` and `confirm_fleet_pause` callbacks.
+const FLEET_PAUSE_CALLBACK_GAS: Gas = Gas::from_tgas(5);
+
+/// Gas attached to the status method of each contract by `pause_all`.
+const FLEET_PAUSE_STATUS_GAS: Gas = Gas::from_tgas(5);
+
+/// Amount of gas used by `pause_all` to pause one contract without taking into account the gas
+/// attached to the promises.
+const FLEET_PAUSE_TARGET_GAS: Gas = Gas::from_tgas(5);
+
+/// Amount of gas reserved in `pause_all` for storing the state of the fleet pause.
+const FLEET_PAUSE_RESERVED_GAS: Gas = Gas::from_tgas(15);
+
+/// Gas needed to call the `finish_delegated_execution` callback.
+const DELEGATED_EXECUTION_CALLBACK_GAS: Gas = Gas::from_tgas(5);
+
+/// Maximal number of the delegated executions stored in the history.
+const MAX_EXECUTION_HISTORY: u64 = 100;
+
+/// Gas needed to call the callbacks of the delegated execution plan.
+const EXECUTION_STEP_CALLBACK_GAS: Gas = Gas::from_tgas(10);
+
+/// Gas needed by the `finish_execution_plan` callback to record the result of one step.
+const EXECUTION_STEP_RESULT_GAS: Gas = Gas::from_tgas(2);
+
+/// Default number of the items returned by the paginated views.
+const DEFAULT_PAGE_LIMIT: u32 = 100;
 
 macro_rules! panic {
     ($($args:tt)*) => {
@@ -50,8 +112,20 @@ macro_rules! panic {
 }
 
 /// ACL Roles of the contract.
-#[derive(AccessControlRole, Deserialize, Serialize, Copy, Clone)]
+#[derive(
+    AccessControlRole,
+    Deserialize,
+    Serialize,
+    BorshDeserialize,
+    BorshSerialize,
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+)]
 #[serde(crate = "near_sdk::serde")]
+#[borsh(crate = "near_sdk::borsh")]
 pub enum Role {
     DAO,
     Deployer,
@@ -60,6 +134,7 @@ pub enum Role {
     Updater,
     Unpauser,
     Downgrader,
+    Executor,
 }
 
 /// Controller contract for deploying and upgrading contracts.
@@ -82,6 +157,41 @@ pub struct AuroraControllerFactory {
     blobs: IterableMap<String, Vec<u8>>,
     deployments: IterableMap<AccountId, DeploymentInfo>,
     latest: LazyOption<ReleaseInfo>,
+    blob_uploads: LookupMap<String, BlobUpload>,
+    blob_chunks: LookupMap<(String, u32), Vec<u8>>,
+    storage_stakes: LookupMap<String, ReleaseStorageStake>,
+    storage_treasury: Option<AccountId>,
+    batch_upgrades: IterableMap<u64, BatchUpgrade>,
+    next_batch_id: u64,
+    rollouts: IterableMap<u64, Rollout>,
+    next_rollout_id: u64,
+    upgrade_delays: LookupMap<AccountId, u64>,
+    scheduled_upgrades: IterableMap<AccountId, ScheduledUpgrade>,
+    proposal_policy: Option<ProposalPolicy>,
+    proposals: IterableMap<u64, Proposal>,
+    next_proposal_id: u64,
+    channels: IterableMap<String, String>,
+    family_upgrade_methods: LookupMap<Option<String>, UpgradeMethod>,
+    deployment_upgrade_methods: LookupMap<AccountId, UpgradeMethod>,
+    family_upgrade_policies: LookupMap<Option<String>, UpgradePolicy>,
+    release_upgrade_policies: LookupMap<String, UpgradePolicy>,
+    code_drifts: IterableMap<AccountId, CodeDrift>,
+    default_access_keys: Vec<AccessKeyInfo>,
+    account_creator: Option<AccountId>,
+    failed_deployments: IterableMap<AccountId, FailedDeployment>,
+    refund_beneficiary: Option<AccountId>,
+    family_pause_methods: LookupMap<Option<String>, PauseMethods>,
+    deployment_pause_methods: LookupMap<AccountId, PauseMethods>,
+    fleet_pauses: IterableMap<u64, FleetPause>,
+    next_fleet_pause_id: u64,
+    deployment_fleet_pauses: LookupMap<AccountId, u64>,
+    family_delegation_policies: LookupMap<Option<String>, DelegationPolicy>,
+    deployment_delegation_policies: LookupMap<AccountId, DelegationPolicy>,
+    executions: LookupMap<u64, ExecutionRecord>,
+    next_execution_id: u64,
+    family_latest_releases: LookupMap<String, String>,
+    family_production_flags: LookupMap<Option<String>, bool>,
+    deployment_production_flags: LookupMap<AccountId, bool>,
 }
 
 #[near]
@@ -95,12 +205,7 @@ impl AuroraControllerFactory {
     #[init]
     #[allow(clippy::use_self)]
     pub fn new(dao: Option<AccountId>) -> Self {
-        let mut contract = Self {
-            releases: IterableMap::new(keys::Prefix::Releases),
-            blobs: IterableMap::new(keys::Prefix::Blobs),
-            deployments: IterableMap::new(keys::Prefix::Deployments),
-            latest: LazyOption::new(keys::Prefix::LatestRelease, None),
-        };
+        let mut contract = Self::empty_state();
 
         require!(
             contract.acl_init_super_admin(env::current_account_id()),
@@ -116,6 +221,34 @@ impl AuroraControllerFactory {
         contract
     }
 
+    /// Migrates the state of the controller of the versions 0.3.x to the current layout. It's the
+    /// single migration of the release, which covers all changes of the layout made since then:
+    /// the releases and the deployments are rewritten with the fields added to them, which are
+    /// left unset, and the rest of the state is initialized as in `new`. Could be called by `DAO`
+    /// or by the controller itself after `up_deploy_code`.
+    ///
+    /// # Panics
+    ///
+    /// The function panics if the state is already in the current layout or the caller isn't
+    /// `DAO`.
+    #[must_use]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let migration::VersionedState::V0(state) = migration::VersionedState::read() else {
+            panic!("state of the contract is already migrated");
+        };
+        let mut contract = Self::empty_state();
+
+        require!(
+            env::predecessor_account_id() == env::current_account_id()
+                || contract.acl_has_role(Role::DAO.into(), env::predecessor_account_id()),
+            "only DAO could migrate the state of the contract"
+        );
+
+        state.migrate_into(&mut contract);
+        contract
+    }
+
     /// Returns version of the controller contract.
     #[must_use]
     pub const fn version(&self) -> &'static str {
@@ -127,464 +260,4502 @@ impl AuroraControllerFactory {
     #[payable]
     pub fn attach_full_access_key(&mut self, public_key: PublicKey) -> Promise {
         assert_one_yocto();
-        event::emit(
-            Event::AttachFullAccessKey,
-            &json!({"public_key": &public_key}),
-        );
-        Promise::new(env::current_account_id()).add_full_access_key(public_key)
+        self.assert_no_proposal_policy();
+        Self::attach_full_access_key_internal(public_key)
     }
 
-    /// Delegates an execution of actions to the specified receiver.
-    #[access_control_any(roles(Role::DAO))]
+    /// Delegates an execution of actions to the specified receiver. The actions of the accounts
+    /// without the `DAO` role are allowed for the deployed contracts only and restricted by
+    /// the delegation policy of the receiver. The `DAO` role is exempt from both checks and could
+    /// call any account with any actions, as before the `Executor` role was added, while
+    /// the proposal policy isn't set.
+    #[access_control_any(roles(Role::DAO, Role::Executor))]
     #[payable]
-    pub fn delegate_execution(
-        &mut self,
-        receiver_id: AccountId,
-        actions: Vec<FunctionCallArgs>,
-    ) -> Promise {
+    pub fn delegate_execution(&mut self, receiver_id: AccountId, actions: Vec<FunctionCallArgs>) {
         require!(
             !env::attached_deposit().is_zero(),
             "required at least 1 yoctoNEAR",
         );
 
-        let log_actions = actions
+        if self.acl_has_role(Role::DAO.into(), env::predecessor_account_id()) {
+            self.assert_no_proposal_policy();
+        } else {
+            self.check_delegated_actions(&receiver_id, &actions)
+                .unwrap_or_else(|e| env::panic_str(&e));
+        }
+
+        let (calls, execution_id) =
+            self.delegate_execution_internal(receiver_id, actions, env::attached_deposit());
+        calls
+            .as_return()
+            .then(Self::execution_callback(execution_id));
+    }
+
+    /// Callback which records the result of the delegated execution in the history. The value
+    /// returned by the receiver is returned as is. The callback doesn't fail if the receiver
+    /// has failed, so `delegate_execution`, `delegate_pause` and `delegate_unpause` return
+    /// the result of the receiver instead, and their transactions fail with it.
+    #[private]
+    pub fn finish_delegated_execution(&mut self, execution_id: u64) {
+        if let Some(output) = self.finish_execution(execution_id, 0) {
+            env::value_return(&output);
+        }
+    }
+
+    /// Returns the delegated execution from the history.
+    #[must_use]
+    pub fn get_execution(&self, execution_id: u64) -> Option<ExecutionRecord> {
+        self.executions.get(&execution_id).cloned()
+    }
+
+    /// Returns a page of the delegated executions from the history starting from the provided
+    /// or the oldest stored id.
+    #[must_use]
+    pub fn get_executions(
+        &self,
+        from_id: Option<u64>,
+        limit: Option<u32>,
+    ) -> BTreeMap<u64, ExecutionRecord> {
+        let first_id = self.next_execution_id.saturating_sub(MAX_EXECUTION_HISTORY);
+
+        (from_id.unwrap_or_default().max(first_id)..self.next_execution_id)
+            .filter_map(|id| self.executions.get(&id).map(|record| (id, record.clone())))
+            .take(to_usize(limit.unwrap_or(DEFAULT_PAGE_LIMIT)))
+            .collect()
+    }
+
+    /// Delegates an execution of the steps with the actions to many receivers. The steps are
+    /// executed in parallel or one by one in the provided order. The results of the steps are
+    /// collected by the final callback and emitted in the `execution_plan_completed` event.
+    /// The deposit which exceeds the deposits of the actions is refunded to the caller at once,
+    /// the deposits of the skipped steps are refunded by the final callback.
+    /// The receivers and the actions are checked as in `delegate_execution`, so the `DAO` role
+    /// isn't restricted to the deployed contracts and their delegation policies.
+    #[access_control_any(roles(Role::DAO, Role::Executor))]
+    #[payable]
+    pub fn delegate_execution_plan(
+        &mut self,
+        steps: Vec<ExecutionStep>,
+        mode: ExecutionMode,
+    ) -> Promise {
+        require!(!steps.is_empty(), "execution plan doesn't have steps");
+        for step in &steps {
+            require!(
+                !step.actions.is_empty(),
+                format!(
+                    "step for the receiver: {} doesn't have actions",
+                    step.receiver_id
+                )
+            );
+        }
+        let total_amount = steps
             .iter()
-            .map(LogFunctionCallArgs::from)
+            .flat_map(|step| &step.actions)
+            .try_fold(NearToken::from_near(0), |total, action| {
+                total.checked_add(action.amount)
+            });
+        let attached_deposit = env::attached_deposit();
+        let total_amount = total_amount
+            .filter(|total| !attached_deposit.is_zero() && *total <= attached_deposit)
+            .unwrap_or_else(|| panic!("not enough deposit attached"));
+
+        if self.acl_has_role(Role::DAO.into(), env::predecessor_account_id()) {
+            self.assert_no_proposal_policy();
+        } else {
+            for step in &steps {
+                self.check_delegated_actions(&step.receiver_id, &step.actions)
+                    .unwrap_or_else(|e| env::panic_str(&e));
+            }
+        }
+
+        let execution_ids = steps
+            .iter()
+            .map(|step| {
+                let method_names = step
+                    .actions
+                    .iter()
+                    .map(|action| action.function_name.clone())
+                    .collect();
+                self.start_execution(&step.receiver_id, method_names)
+            })
             .collect::<Vec<_>>();
 
         event::emit(
-            Event::DelegatedExecution,
+            Event::DelegatedExecutionPlan,
             &json!({
-                "receiver_id": &receiver_id,
-                "actions": log_actions,
+                "mode": mode,
+                "steps": steps
+                    .iter()
+                    .zip(&execution_ids)
+                    .map(|(step, execution_id)| json!({
+                        "execution_id": execution_id,
+                        "receiver_id": &step.receiver_id,
+                        "actions": step
+                            .actions
+                            .iter()
+                            .map(LogFunctionCallArgs::from)
+                            .collect::<Vec<_>>(),
+                    }))
+                    .collect::<Vec<_>>(),
             }),
         );
 
-        let mut total = env::attached_deposit();
-        actions
+        let caller = env::predecessor_account_id();
+        let surplus = attached_deposit.saturating_sub(total_amount);
+        if !surplus.is_zero() {
+            Promise::new(caller.clone()).transfer(surplus);
+        }
+
+        let mut steps = steps;
+        match mode {
+            ExecutionMode::Parallel => {
+                let receiver_ids = steps.iter().map(|step| step.receiver_id.clone()).collect();
+                // The callback gets the unused gas as well, since the size of the outputs which
+                // it copies into the event isn't known in advance.
+                let callback_gas = EXECUTION_STEP_RESULT_GAS
+                    .saturating_mul(u64::try_from(steps.len()).unwrap_or(u64::MAX))
+                    .saturating_add(EXECUTION_STEP_CALLBACK_GAS);
+                steps
+                    .into_iter()
+                    .map(step_promise)
+                    .reduce(Promise::and)
+                    .unwrap_or_else(|| panic!("execution plan doesn't have steps"))
+                    .then(
+                        Self::ext(env::current_account_id())
+                            .with_static_gas(callback_gas)
+                            .with_unused_gas_weight(1)
+                            .finish_execution_plan(receiver_ids, execution_ids),
+                    )
+            }
+            ExecutionMode::Sequential => {
+                let step = steps.remove(0);
+                Self::execute_step(caller, execution_ids, step, steps, vec![])
+            }
+        }
+    }
+
+    /// Callback which collects the results of the steps executed in parallel and records them
+    /// in the history of the delegated executions.
+    #[private]
+    pub fn finish_execution_plan(
+        &mut self,
+        receiver_ids: Vec<AccountId>,
+        execution_ids: Vec<u64>,
+    ) -> Vec<ExecutionStepResult> {
+        let results = receiver_ids
             .into_iter()
-            .fold(Promise::new(receiver_id), |promise, action| {
-                total = total
-                    .checked_sub(action.amount)
-                    .unwrap_or_else(|| env::panic_str("not enough deposit attached"));
-                promise.function_call(
-                    action.function_name,
-                    action.arguments.into(),
-                    action.amount,
-                    action.gas,
+            .zip(execution_ids)
+            .enumerate()
+            .map(|(index, (receiver_id, execution_id))| {
+                self.finish_step(
+                    execution_id,
+                    receiver_id,
+                    u64::try_from(index).unwrap_or(u64::MAX),
                 )
             })
+            .collect::<Vec<_>>();
+
+        event::emit(Event::ExecutionPlanCompleted, &json!({"results": &results}));
+        results
     }
 
-    /// Pauses the contract with provided account id.
-    #[access_control_any(roles(Role::DAO, Role::Pauser))]
-    #[payable]
-    pub fn delegate_pause(
+    /// Callback which records the result of the step executed in order and starts the next step.
+    /// The remaining steps are skipped if the step has failed, and the deposits of their actions
+    /// are refunded to the caller. The first of the execution ids belongs to the finished step,
+    /// the rest ones belong to the remaining steps.
+    #[private]
+    pub fn finish_execution_step(
         &mut self,
+        caller: AccountId,
+        execution_ids: Vec<u64>,
         receiver_id: AccountId,
-        pause_method_name: Option<String>,
-        pause_arguments: Option<Value>,
-    ) -> Promise {
-        assert_one_yocto();
-        let function_name = match pause_method_name {
-            Some(method) if ALLOWED_PAUSE_METHODS.contains(&method.as_str()) => method,
-            Some(method) => panic!("pause method: {method} is not allowed"),
-            None => "pause_contract".to_string(), // Aurora Engine pause method name is used by default.
+        steps: Vec<ExecutionStep>,
+        results: Vec<ExecutionStepResult>,
+    ) -> PromiseOrValue<Vec<ExecutionStepResult>> {
+        let Some((&execution_id, next_ids)) = execution_ids.split_first() else {
+            panic!("execution ids don't match the steps");
         };
+        let result = self.finish_step(execution_id, receiver_id, 0);
+        let is_success = result.status == ExecutionStepStatus::Succeeded;
+        let mut results = results;
+        let mut steps = steps;
+        results.push(result);
 
-        event::emit(
-            Event::DelegatedPause,
-            &json!({
-                "receiver_id": &receiver_id,
-                "pause_method_name": &function_name,
-                "pause_arguments": pause_arguments.as_ref().unwrap_or(&Value::Null),
-            }),
-        );
+        if is_success && !steps.is_empty() {
+            let step = steps.remove(0);
+            return PromiseOrValue::Promise(Self::execute_step(
+                caller,
+                next_ids.to_vec(),
+                step,
+                steps,
+                results,
+            ));
+        }
 
-        let arguments = pause_arguments.map_or_else(Vec::new, |args| {
-            near_sdk::serde_json::to_vec(&args)
-                .unwrap_or_else(|e| panic!("bad format of the pause arguments: {e}"))
-        });
-        let gas = env::prepaid_gas().saturating_sub(OUTER_DELEGATE_PAUSE_GAS);
+        let skipped_deposit = steps
+            .iter()
+            .flat_map(|step| &step.actions)
+            .fold(NearToken::from_near(0), |total, action| {
+                total.saturating_add(action.amount)
+            });
+        if !skipped_deposit.is_zero() {
+            Promise::new(caller).transfer(skipped_deposit);
+        }
 
-        Promise::new(receiver_id).function_call(
-            function_name,
-            arguments,
-            NearToken::from_near(0),
-            gas,
-        )
+        for (step, &execution_id) in steps.into_iter().zip(next_ids) {
+            self.set_execution_status(execution_id, ExecutionStatus::Skipped);
+            results.push(ExecutionStepResult {
+                execution_id,
+                receiver_id: step.receiver_id,
+                status: ExecutionStepStatus::Skipped,
+                output: None,
+            });
+        }
+
+        event::emit(Event::ExecutionPlanCompleted, &json!({"results": &results}));
+        PromiseOrValue::Value(results)
     }
 
-    /// Adds new contract release info.
+    /// Sets the delegation policy for the contracts of the family. The contracts without
+    /// the policy can't be called by the `Executor` role.
     #[access_control_any(roles(Role::DAO))]
     #[payable]
-    pub fn add_release_info(
+    pub fn set_family_delegation_policy(
         &mut self,
-        hash: String,
-        version: Version,
-        is_latest: bool,
-        downgrade_hash: Option<String>,
-        description: Option<String>,
+        family: Option<String>,
+        policy: Option<DelegationPolicy>,
     ) {
         assert_one_yocto();
-        require!(
-            self.releases.get(&hash).is_none(),
-            "release info for the hash is already exist"
-        );
-
-        let release_info = ReleaseInfo {
-            hash: hash.clone(),
-            version,
-            is_blob_exist: false,
-            downgrade_hash,
-            description,
-        };
+        if let Some(policy) = &policy {
+            assert_valid_delegation_policy(policy);
+        }
 
-        event::emit(Event::AddReleaseInfo, &release_info);
-        self.releases.insert(hash.clone(), release_info);
+        event::emit(
+            Event::SetFamilyDelegationPolicy,
+            &json!({"family": family_name(family.as_ref()), "policy": &policy}),
+        );
 
-        if is_latest {
-            self.set_latest_release(&hash);
+        if let Some(policy) = policy {
+            self.family_delegation_policies.insert(family, policy);
+        } else {
+            self.family_delegation_policies.remove(&family);
         }
     }
 
-    /// Adds bytes of the contract smart contract to the corresponding release info. The attached
-    /// deposit could be used as a payment for the storage staking.
+    /// Overrides the delegation policy for the contract. If the policy isn't provided, the policy
+    /// of the contract family is used.
+    #[access_control_any(roles(Role::DAO))]
     #[payable]
-    pub fn add_release_blob(&mut self) {
+    pub fn set_deployment_delegation_policy(
+        &mut self,
+        contract_id: AccountId,
+        policy: Option<DelegationPolicy>,
+    ) {
+        assert_one_yocto();
         require!(
-            !env::attached_deposit().is_zero(),
-            "required at least 1 yoctoNEAR"
+            self.deployments.contains_key(&contract_id),
+            format!("contract with account id: {contract_id} hasn't been deployed")
         );
-        let blob = env::input().unwrap_or_else(|| panic!("no blob's bytes were provided"));
-        let hash = utils::hash_256(&blob);
-        let release_info = self.releases.get_mut(&hash).unwrap_or_else(|| {
-            panic!("release info doesn't exist for the hash: {hash}");
-        });
+        if let Some(policy) = &policy {
+            assert_valid_delegation_policy(policy);
+        }
 
-        event::emit(Event::AddBlob, &json!({"blob_hash": &hash}));
+        event::emit(
+            Event::SetDeploymentDelegationPolicy,
+            &json!({"contract_id": &contract_id, "policy": &policy}),
+        );
 
-        release_info.is_blob_exist = true;
-        self.blobs.insert(hash, blob);
+        if let Some(policy) = policy {
+            self.deployment_delegation_policies
+                .insert(contract_id, policy);
+        } else {
+            self.deployment_delegation_policies.remove(&contract_id);
+        }
     }
 
-    /// Marks the release with the hash: `hash` as latest.
-    #[access_control_any(roles(Role::DAO, Role::Releaser))]
-    #[payable]
-    pub fn set_latest_release(&mut self, hash: &String) {
-        assert_one_yocto();
-        let new_latest = self.releases.get(hash).unwrap_or_else(|| {
-            panic!("release info doesn't exist for hash: {hash}");
-        });
-
-        if let Some(current_latest) = self.latest.get() {
-            assert!(
-                current_latest.version < new_latest.version,
-                "version of new latest should be higher than previous"
-            );
-        }
+    /// Returns the delegation policy for the contracts of the family.
+    #[must_use]
+    pub fn get_family_delegation_policy(&self, family: Option<String>) -> Option<DelegationPolicy> {
+        self.family_delegation_policies.get(&family).cloned()
+    }
 
-        self.latest.set(new_latest);
-        event::emit(Event::SetLatestReleaseInfo, new_latest);
+    /// Returns the delegation policy for the contract.
+    #[must_use]
+    pub fn get_delegation_policy(&self, contract_id: &AccountId) -> Option<DelegationPolicy> {
+        self.deployment_delegation_policies
+            .get(contract_id)
+            .cloned()
+            .or_else(|| {
+                let family = self.deployments.get(contract_id)?.family.clone();
+                self.get_family_delegation_policy(family)
+            })
     }
 
-    /// Removes the release info for hash: `hash`.
+    /// Sets the policy of the proposals. While the policy is set, the sensitive actions could be
+    /// executed by the approved proposals only, and the policy could be changed by the proposal.
+    /// The threshold of the policy shouldn't exceed the number of the members of its roles.
     #[access_control_any(roles(Role::DAO))]
     #[payable]
-    pub fn remove_release(&mut self, hash: &String) {
+    pub fn set_proposal_policy(&mut self, policy: ProposalPolicy) {
         assert_one_yocto();
-        let release_info = self.releases.remove(hash).unwrap_or_else(|| {
-            panic!("release info doesn't exist for hash: {hash}");
-        });
-        self.blobs.remove(hash);
-        event::emit(Event::RemoveReleaseInfo, &release_info);
+        self.assert_no_proposal_policy();
+        self.set_proposal_policy_internal(Some(policy));
     }
 
-    /// Returns a list of existing releases for deployment.
+    /// Returns the policy of the proposals.
     #[must_use]
-    pub fn get_releases(&self) -> Vec<ReleaseInfo> {
-        self.releases.values().cloned().collect()
+    pub const fn get_proposal_policy(&self) -> Option<&ProposalPolicy> {
+        self.proposal_policy.as_ref()
     }
 
-    /// Returns a hash of the latest release.
-    #[must_use]
-    pub fn get_latest_release_hash(&self) -> String {
-        self.latest.get().map_or_else(
-            || panic!("the latest release hash hasn't been set yet"),
-            |r| r.hash,
-        )
+    /// Creates a proposal of the sensitive action. The proposal should be approved by the members
+    /// of the roles from the policy before its expiration. The deposit for the delegated execution
+    /// should be attached while creating the proposal.
+    #[payable]
+    pub fn create_proposal(&mut self, action: ProposalAction) -> u64 {
+        let policy = self.assert_proposal_member();
+        let deposit = env::attached_deposit();
+
+        match &action {
+            ProposalAction::DelegateExecution { actions, .. } => {
+                let total = actions
+                    .iter()
+                    .try_fold(NearToken::from_yoctonear(0), |total, action| {
+                        total.checked_add(action.amount)
+                    })
+                    .unwrap_or_else(|| env::panic_str("total amount of the actions overflows"));
+                require!(
+                    !deposit.is_zero() && deposit >= total,
+                    "not enough deposit attached"
+                );
+            }
+            ProposalAction::UnrestrictedUpgrade {
+                contract_id, hash, ..
+            } => {
+                assert_one_yocto();
+                self.assert_not_time_locked(contract_id);
+                require!(
+                    self.releases.get(hash).is_some_and(|r| r.is_blob_exist),
+                    format!("blob doesn't exist for hash: {hash}")
+                );
+            }
+            ProposalAction::AttachFullAccessKey { .. } => assert_one_yocto(),
+            ProposalAction::SetProposalPolicy { policy: new_policy } => {
+                assert_one_yocto();
+                if let Some(new_policy) = new_policy {
+                    self.assert_valid_proposal_policy(new_policy);
+                }
+            }
+        }
+
+        let proposal_id = self.next_proposal_id;
+        self.next_proposal_id += 1;
+        let created_at = env::block_timestamp();
+        let proposal = Proposal {
+            proposer: env::predecessor_account_id(),
+            action,
+            approvals: BTreeSet::new(),
+            deposit,
+            created_at,
+            expires_at: created_at.saturating_add(policy.duration),
+            is_executed: false,
+        };
+
+        event::emit(
+            Event::CreateProposal,
+            &json!({"proposal_id": proposal_id, "proposal": &proposal}),
+        );
+        self.proposals.insert(proposal_id, proposal);
+
+        proposal_id
     }
 
-    /// Deploys a new contract on the release info that corresponds to the provided hash.
-    #[access_control_any(roles(Role::DAO, Role::Deployer))]
+    /// Approves the proposal. The proposal is executed once the threshold of approvals is met.
     #[payable]
-    pub fn deploy(
-        &mut self,
-        new_contract_id: AccountId,
-        init_method: String,
-        init_args: Value,
-        blob_hash: Option<String>,
-    ) -> Promise {
+    pub fn approve_proposal(&mut self, proposal_id: u64) -> PromiseOrValue<()> {
+        assert_one_yocto();
+        let policy = self.assert_proposal_member();
+        let approver = env::predecessor_account_id();
+        let proposal = self
+            .proposals
+            .get_mut(&proposal_id)
+            .unwrap_or_else(|| panic!("proposal with id: {proposal_id} doesn't exist"));
+        require!(!proposal.is_executed, "proposal has been already executed");
         require!(
-            !env::attached_deposit().is_zero(),
-            "required at least 1 yoctonear"
+            !proposal.is_expired(env::block_timestamp()),
+            "proposal has been expired"
         );
-        // Check that the `new_contract_id` wasn't used for another contract before.
         require!(
-            self.deployments.get(&new_contract_id).is_none(),
-            format!("{new_contract_id} is already deployed")
+            proposal.approvals.insert(approver.clone()),
+            "proposal has been already approved by the account"
         );
 
-        let blob_hash = blob_hash
-            .or_else(|| self.latest.get().map(|r| r.hash))
-            .unwrap_or_else(|| panic!("no custom hash nor the latest was provided"));
-        let release_info = self
-            .releases
-            .get(&blob_hash)
-            .unwrap_or_else(|| panic!("no release info for hash: {}", &blob_hash));
-        let event_metadata =
-            json!({"contract_id": &new_contract_id, "release_info": &release_info});
-        let code = self
-            .blobs
-            .get(&blob_hash)
-            .unwrap_or_else(|| panic!("blob doesn't exist for hash: {}", &blob_hash));
-        let init_args_string = near_sdk::serde_json::to_string(&init_args)
-            .unwrap_or_else(|e| panic!("bad format of the init args: {e}"));
+        event::emit(
+            Event::ApproveProposal,
+            &json!({"proposal_id": proposal_id, "approver": &approver}),
+        );
 
-        event::emit(Event::Deploy, &event_metadata);
+        if proposal.approvals.len() < usize::try_from(policy.threshold).unwrap_or(usize::MAX) {
+            return PromiseOrValue::Value(());
+        }
 
-        let block_time = env::block_timestamp();
-        let deployment_info = DeploymentInfo {
-            hash: blob_hash,
-            version: release_info.version.clone(),
-            deployment_time: block_time,
-            upgrade_times: [(block_time, release_info.version.clone())].into(),
-            init_args: init_args_string.clone(),
-        };
+        proposal.is_executed = true;
+        let action = proposal.action.clone();
+        let deposit = proposal.deposit;
+        let proposer = proposal.proposer.clone();
+        event::emit(
+            Event::ExecuteProposal,
+            &json!({"proposal_id": proposal_id, "action": &action}),
+        );
 
-        Promise::new(new_contract_id.clone())
-            .create_account()
-            .add_full_access_key(env::signer_account_pk())
-            .transfer(env::attached_deposit())
-            .deploy_contract(code.clone())
-            .function_call(
-                init_method,
-                init_args_string.into_bytes(),
-                NearToken::from_near(0),
-                NEW_GAS,
-            )
-            .then(
-                Self::ext(env::current_account_id())
-                    .with_static_gas(ADD_DEPLOYMENT_GAS)
-                    .update_deployment_info(new_contract_id, deployment_info),
-            )
+        self.execute_proposal_action(action, deposit, proposer)
     }
 
-    /// Adds new deployment info of previously deployed contract.
-    /// E.g. the contract which has been deployed by not this controller contract.
-    #[access_control_any(roles(Role::DAO))]
+    /// Removes the executed or expired proposal. The deposit of the expired proposal is returned
+    /// to the proposer.
     #[payable]
-    pub fn add_deployment_info(&mut self, contract_id: AccountId, deployment_info: DeploymentInfo) {
+    pub fn remove_proposal(&mut self, proposal_id: u64) {
         assert_one_yocto();
+        let proposal = self
+            .proposals
+            .get(&proposal_id)
+            .unwrap_or_else(|| panic!("proposal with id: {proposal_id} doesn't exist"));
+        require!(
+            proposal.is_executed || proposal.is_expired(env::block_timestamp()),
+            "proposal is still active"
+        );
+
+        if !proposal.is_executed {
+            Promise::new(proposal.proposer.clone()).transfer(proposal.deposit);
+        }
+
         event::emit(
-            Event::AddDeploymentInfo,
-            &json!({"contract_id": contract_id, "deployment_info": deployment_info}),
+            Event::RemoveProposal,
+            &json!({"proposal_id": proposal_id, "proposal": proposal}),
         );
-        self.deployments.insert(contract_id, deployment_info);
+        self.proposals.remove(&proposal_id);
     }
 
-    /// Callback which adds new deployment info after successful deployment of new contract.
-    #[private]
-    pub fn update_deployment_info(
-        &mut self,
-        contract_id: AccountId,
-        deployment_info: DeploymentInfo,
-    ) {
-        let result = env::promise_result(0);
-
-        if matches!(result, PromiseResult::Successful(_)) {
-            event::emit(
-                Event::UpdateDeploymentInfo,
-                &json!({"contract_id": contract_id, "deployment_info": deployment_info}),
-            );
-            self.deployments.insert(contract_id, deployment_info);
-        }
+    /// Returns the proposal with corresponding id.
+    #[must_use]
+    pub fn get_proposal(&self, proposal_id: u64) -> Option<Proposal> {
+        self.proposals.get(&proposal_id).cloned()
     }
 
-    /// Returns a list of existing contract deployment infos.
+    /// Returns a list of existing proposals.
     #[must_use]
-    pub fn get_deployments(&self) -> BTreeMap<AccountId, DeploymentInfo> {
-        self.deployments
+    pub fn get_proposals(&self) -> BTreeMap<u64, Proposal> {
+        self.proposals
             .iter()
-            .map(|(acc, info)| (acc.clone(), info.clone()))
+            .map(|(id, proposal)| (*id, proposal.clone()))
             .collect()
     }
 
-    /// Returns a contract deployment info for corresponding account id.
-    #[must_use]
-    pub fn get_deployment(&self, account_id: &AccountId) -> Option<DeploymentInfo> {
-        self.deployments.get(account_id).cloned()
+    /// Pauses the contract with provided account id. The method should be one of the pause
+    /// methods allowed for the contract, the first one is used if it isn't provided.
+    #[access_control_any(roles(Role::DAO, Role::Pauser))]
+    #[payable]
+    pub fn delegate_pause(
+        &mut self,
+        receiver_id: AccountId,
+        pause_method_name: Option<String>,
+        pause_arguments: Option<Value>,
+    ) {
+        assert_one_yocto();
+        let function_name = resolve_pause_method(
+            &self.get_pause_methods(&receiver_id).pause,
+            pause_method_name,
+            "pause",
+        );
+
+        let execution_id = self.start_execution(&receiver_id, vec![function_name.clone()]);
+
+        event::emit(
+            Event::DelegatedPause,
+            &json!({
+                "execution_id": execution_id,
+                "receiver_id": &receiver_id,
+                "pause_method_name": &function_name,
+                "pause_arguments": pause_arguments.as_ref().unwrap_or(&Value::Null),
+            }),
+        );
+
+        pause_call(receiver_id, function_name, pause_arguments)
+            .as_return()
+            .then(Self::execution_callback(execution_id));
     }
 
-    /// Upgrades a contract with account id and provided or the latest hash.
-    #[access_control_any(roles(Role::DAO, Role::Updater))]
+    /// Unpauses the contract with provided account id. The method should be one of the unpause
+    /// methods allowed for the contract, the first one is used if it isn't provided.
+    #[access_control_any(roles(Role::DAO, Role::Unpauser))]
     #[payable]
-    pub fn upgrade(
+    pub fn delegate_unpause(
         &mut self,
-        contract_id: AccountId,
-        hash: Option<String>,
-        state_migration_gas: Option<u64>,
-    ) -> Promise {
+        receiver_id: AccountId,
+        unpause_method_name: Option<String>,
+        unpause_arguments: Option<Value>,
+    ) {
         assert_one_yocto();
+        let function_name = resolve_pause_method(
+            &self.get_pause_methods(&receiver_id).unpause,
+            unpause_method_name,
+            "unpause",
+        );
 
-        self.upgrade_internal(
-            contract_id,
-            hash,
-            false,
-            state_migration_gas,
-            Event::Upgrade,
-        )
+        let execution_id = self.start_execution(&receiver_id, vec![function_name.clone()]);
+
+        event::emit(
+            Event::DelegatedUnpause,
+            &json!({
+                "execution_id": execution_id,
+                "receiver_id": &receiver_id,
+                "unpause_method_name": &function_name,
+                "unpause_arguments": unpause_arguments.as_ref().unwrap_or(&Value::Null),
+            }),
+        );
+
+        let callback = match self.deployment_fleet_pauses.get(&receiver_id) {
+            Some(fleet_pause_id) => Self::ext(env::current_account_id())
+                .with_static_gas(DELEGATED_EXECUTION_CALLBACK_GAS)
+                .with_unused_gas_weight(0)
+                .finish_delegated_unpause(execution_id, receiver_id.clone(), *fleet_pause_id),
+            None => Self::execution_callback(execution_id),
+        };
+
+        pause_call(receiver_id, function_name, unpause_arguments)
+            .as_return()
+            .then(callback);
     }
 
-    /// Upgrades a contract with account id and provided hash without checking version.
-    #[access_control_any(roles(Role::DAO))]
+    /// Callback which records the result of the delegated unpause of the contract paused by
+    /// the fleet pause and marks the contract as unpaused in the fleet pause after success.
+    #[private]
+    pub fn finish_delegated_unpause(
+        &mut self,
+        execution_id: u64,
+        receiver_id: AccountId,
+        fleet_pause_id: u64,
+    ) {
+        if matches!(env::promise_result(0), PromiseResult::Successful(_)) {
+            self.update_fleet_pause_status(fleet_pause_id, receiver_id, PauseStatus::Unpaused);
+        }
+
+        self.finish_delegated_execution(execution_id);
+    }
+
+    /// Pauses the contracts with provided account ids or, if they aren't provided, the contracts
+    /// matched by the filter by the first pause method allowed for each contract. Returns id of
+    /// the fleet pause which tracks the confirmations of the pauses. The pauses which don't fit
+    /// into the attached gas stay pending and could be started by `resume_fleet_pause`.
+    #[access_control_any(roles(Role::DAO, Role::Pauser))]
     #[payable]
-    pub fn unrestricted_upgrade(
+    pub fn pause_all(
         &mut self,
-        contract_id: AccountId,
-        hash: String,
-        state_migration_gas: Option<u64>,
-    ) -> Promise {
+        contract_ids: Option<Vec<AccountId>>,
+        filter: Option<DeploymentFilter>,
+        pause_arguments: Option<Value>,
+    ) -> u64 {
         assert_one_yocto();
-        self.upgrade_internal(
-            contract_id,
-            Some(hash),
-            true,
-            state_migration_gas,
-            Event::UnrestrictedUpgrade,
-        )
+        let contract_ids = contract_ids.unwrap_or_else(|| self.select_deployments(filter));
+        require!(!contract_ids.is_empty(), "no contracts to pause");
+
+        let fleet_pause_id = self.next_fleet_pause_id;
+        self.next_fleet_pause_id += 1;
+        let pause_arguments = pause_arguments.map(|args| args.to_string());
+
+        event::emit(
+            Event::FleetPause,
+            &json!({
+                "fleet_pause_id": fleet_pause_id,
+                "contract_ids": &contract_ids,
+                "pause_arguments": &pause_arguments
+            }),
+        );
+
+        for contract_id in &contract_ids {
+            self.deployment_fleet_pauses
+                .insert(contract_id.clone(), fleet_pause_id);
+        }
+        self.fleet_pauses.insert(
+            fleet_pause_id,
+            FleetPause {
+                pause_arguments,
+                created_at: env::block_timestamp(),
+                targets: contract_ids
+                    .into_iter()
+                    .map(|contract_id| (contract_id, PauseStatus::Pending))
+                    .collect(),
+                execution_ids: BTreeMap::new(),
+            },
+        );
+        self.dispatch_fleet_pause(fleet_pause_id);
+
+        fleet_pause_id
     }
 
-    /// Downgrades the contract with account id.
-    #[access_control_any(roles(Role::DAO, Role::Downgrader))]
+    /// Starts the pending pauses of the fleet pause which haven't fit into the gas of
+    /// the previous transactions.
+    #[access_control_any(roles(Role::DAO, Role::Pauser))]
     #[payable]
-    pub fn downgrade(&mut self, contract_id: AccountId) -> Promise {
+    pub fn resume_fleet_pause(&mut self, fleet_pause_id: u64) {
         assert_one_yocto();
-        let mut deployment_info =
-            self.deployments
-                .get(&contract_id)
-                .cloned()
-                .unwrap_or_else(|| {
-                    panic!("contract with account id: {contract_id} hasn't been deployed")
-                });
-        let release_info = self.releases.get(&deployment_info.hash).unwrap_or_else(|| {
-            panic!(
-                "release info doesn't exist for hash: {}",
-                &deployment_info.hash
-            )
-        });
-        let downgrade_hash = release_info
-            .downgrade_hash
-            .clone()
-            .unwrap_or_else(|| panic!("release info doesn't include downgrade hash"));
-        let downgrade_release_info = self
-            .releases
-            .get(&downgrade_hash)
-            .unwrap_or_else(|| panic!("no release info for hash: {downgrade_hash}"));
-        let event_metadata =
-            json!({"contract_id": &contract_id, "release_info": &downgrade_release_info});
-        let blob = self.blobs.get(&downgrade_hash).unwrap_or_else(|| {
-            panic!(
-                "blob doesn't exist for hash: {downgrade_hash} and version: {}",
-                release_info.version
-            )
-        });
+        self.dispatch_fleet_pause(fleet_pause_id);
+    }
+
+    /// Removes the completed fleet pause.
+    #[access_control_any(roles(Role::DAO))]
+    #[payable]
+    pub fn remove_fleet_pause(&mut self, fleet_pause_id: u64) {
+        assert_one_yocto();
+        let fleet_pause = self
+            .fleet_pauses
+            .get(&fleet_pause_id)
+            .unwrap_or_else(|| panic!("fleet pause with id: {fleet_pause_id} doesn't exist"));
+        require!(
+            fleet_pause.is_completed(),
+            "fleet pause hasn't been completed yet"
+        );
+        let contract_ids: Vec<_> = fleet_pause.targets.keys().cloned().collect();
+        self.fleet_pauses.remove(&fleet_pause_id);
 
-        event::emit(Event::Downgrade, &event_metadata);
-        deployment_info.update(downgrade_hash, downgrade_release_info.version.clone());
+        for contract_id in &contract_ids {
+            if self.deployment_fleet_pauses.get(contract_id) == Some(&fleet_pause_id) {
+                self.deployment_fleet_pauses.remove(contract_id);
+            }
+        }
+    }
 
-        let args = UpgradeArgs {
-            code: blob.clone(),
-            state_migration_gas: None,
+    /// Callback which records the result of the pause of the contract in the fleet pause and
+    /// in the history of the delegated executions. If the status method is set, the pause is
+    /// confirmed only after the status method returns that the contract is paused. Otherwise,
+    /// the successful pause is recorded as unverified.
+    #[private]
+    pub fn finish_fleet_pause(
+        &mut self,
+        fleet_pause_id: u64,
+        contract_id: AccountId,
+        execution_id: u64,
+        status_method: Option<String>,
+    ) {
+        let is_success = self.finish_execution(execution_id, 0).is_some();
+
+        match status_method {
+            Some(status_method) if is_success => {
+                let arguments = self
+                    .fleet_pauses
+                    .get(&fleet_pause_id)
+                    .and_then(|fleet_pause| fleet_pause.pause_arguments.clone())
+                    .map(String::into_bytes)
+                    .unwrap_or_default();
+                Promise::new(contract_id.clone())
+                    .function_call(
+                        status_method,
+                        arguments,
+                        NearToken::from_near(0),
+                        FLEET_PAUSE_STATUS_GAS,
+                    )
+                    .then(
+                        Self::ext(env::current_account_id())
+                            .with_static_gas(FLEET_PAUSE_CALLBACK_GAS)
+                            .with_unused_gas_weight(0)
+                            .confirm_fleet_pause(fleet_pause_id, contract_id),
+                    );
+            }
+            _ => {
+                let status = if is_success {
+                    PauseStatus::Unverified
+                } else {
+                    PauseStatus::Failed("pause promise has failed".to_string())
+                };
+                self.update_fleet_pause_status(fleet_pause_id, contract_id, status);
+            }
+        }
+    }
+
+    /// Callback which records the state of the contract returned by the status method in
+    /// the fleet pause.
+    #[private]
+    pub fn confirm_fleet_pause(&mut self, fleet_pause_id: u64, contract_id: AccountId) {
+        let status = match env::promise_result(0) {
+            PromiseResult::Successful(value) => {
+                match near_sdk::serde_json::from_slice::<bool>(&value) {
+                    Ok(true) => PauseStatus::Paused,
+                    Ok(false) => PauseStatus::Failed("contract isn't paused".to_string()),
+                    Err(_) => PauseStatus::Failed("bad format of the pause status".to_string()),
+                }
+            }
+            PromiseResult::Failed => PauseStatus::Failed("status promise has failed".to_string()),
         };
+        self.update_fleet_pause_status(fleet_pause_id, contract_id, status);
+    }
 
-        Self::upgrade_promise(contract_id, args, deployment_info)
+    /// Returns the fleet pause with the statuses of the pauses.
+    #[must_use]
+    pub fn get_fleet_pause(&self, fleet_pause_id: u64) -> Option<FleetPause> {
+        self.fleet_pauses.get(&fleet_pause_id).cloned()
     }
-}
 
-impl AuroraControllerFactory {
-    fn upgrade_internal(
+    /// Returns a page of the deployments with the statuses of their latest fleet pauses.
+    /// The status isn't set for the contracts which haven't been paused by the fleet pause.
+    #[must_use]
+    pub fn get_fleet_pause_status(
         &self,
+        from_index: Option<u32>,
+        limit: Option<u32>,
+    ) -> BTreeMap<AccountId, Option<PauseStatus>> {
+        self.deployments
+            .keys()
+            .skip(to_usize(from_index.unwrap_or_default()))
+            .take(to_usize(limit.unwrap_or(DEFAULT_PAGE_LIMIT)))
+            .map(|contract_id| {
+                let status = self
+                    .deployment_fleet_pauses
+                    .get(contract_id)
+                    .and_then(|fleet_pause_id| self.fleet_pauses.get(fleet_pause_id))
+                    .and_then(|fleet_pause| fleet_pause.targets.get(contract_id))
+                    .cloned();
+                (contract_id.clone(), status)
+            })
+            .collect()
+    }
+
+    /// Sets the pause and unpause methods allowed for the contracts of the family. If the methods
+    /// aren't provided, the methods of Aurora Engine and `near-plugins` are allowed.
+    #[access_control_any(roles(Role::DAO))]
+    #[payable]
+    pub fn set_family_pause_methods(
+        &mut self,
+        family: Option<String>,
+        methods: Option<PauseMethods>,
+    ) {
+        assert_one_yocto();
+        if let Some(methods) = &methods {
+            assert_valid_pause_methods(methods);
+        }
+
+        event::emit(
+            Event::SetFamilyPauseMethods,
+            &json!({"family": family_name(family.as_ref()), "methods": &methods}),
+        );
+
+        if let Some(methods) = methods {
+            self.family_pause_methods.insert(family, methods);
+        } else {
+            self.family_pause_methods.remove(&family);
+        }
+    }
+
+    /// Overrides the pause and unpause methods allowed for the contract. If the methods aren't
+    /// provided, the methods of the contract family are allowed.
+    #[access_control_any(roles(Role::DAO))]
+    #[payable]
+    pub fn set_deployment_pause_methods(
+        &mut self,
         contract_id: AccountId,
-        hash: Option<String>,
-        skip_version_check: bool,
-        state_migration_gas: Option<u64>,
-        event: Event,
-    ) -> Promise {
-        let hash = hash
-            .or_else(|| self.latest.get().map(|r| r.hash))
-            .unwrap_or_else(|| panic!("no latest nor custom hash was provided for upgrading"));
-        let release_info = self
-            .releases
-            .get(&hash)
-            .unwrap_or_else(|| panic!("no release info for hash: {hash}"));
+        methods: Option<PauseMethods>,
+    ) {
+        assert_one_yocto();
+        require!(
+            self.deployments.contains_key(&contract_id),
+            format!("contract with account id: {contract_id} hasn't been deployed")
+        );
+        if let Some(methods) = &methods {
+            assert_valid_pause_methods(methods);
+        }
 
-        let mut deployment_info =
-            self.deployments
-                .get(&contract_id)
-                .cloned()
-                .unwrap_or_else(|| {
-                    panic!("contract with account id: {contract_id} hasn't been deployed")
-                });
+        event::emit(
+            Event::SetDeploymentPauseMethods,
+            &json!({"contract_id": &contract_id, "methods": &methods}),
+        );
+
+        if let Some(methods) = methods {
+            self.deployment_pause_methods.insert(contract_id, methods);
+        } else {
+            self.deployment_pause_methods.remove(&contract_id);
+        }
+    }
+
+    /// Returns the pause and unpause methods allowed for the contracts of the family.
+    #[must_use]
+    pub fn get_family_pause_methods(&self, family: Option<String>) -> PauseMethods {
+        self.family_pause_methods
+            .get(&family)
+            .cloned()
+            .unwrap_or_default()
+    }
 
+    /// Returns the pause and unpause methods allowed for the contract. The methods of the default
+    /// family are allowed for the contracts which aren't deployed by the controller.
+    #[must_use]
+    pub fn get_pause_methods(&self, contract_id: &AccountId) -> PauseMethods {
+        self.deployment_pause_methods
+            .get(contract_id)
+            .cloned()
+            .unwrap_or_else(|| {
+                let family = self
+                    .deployments
+                    .get(contract_id)
+                    .and_then(|info| info.family.clone());
+                self.get_family_pause_methods(family)
+            })
+    }
+
+    /// Adds new contract release info. The attached deposit should cover the storage staking
+    /// of the release info, the surplus is refunded.
+    #[access_control_any(roles(Role::DAO))]
+    #[payable]
+    pub fn add_release_info(
+        &mut self,
+        hash: String,
+        version: Version,
+        is_latest: bool,
+        downgrade_hash: Option<String>,
+        description: Option<String>,
+        family: Option<String>,
+    ) {
         require!(
-            release_info.version > deployment_info.version || skip_version_check,
-            format!(
-                "upgradable version: {} should be higher than the deployed version: {}",
-                release_info.version, deployment_info.version
-            )
+            self.releases.get(&hash).is_none(),
+            "release info for the hash is already exist"
         );
 
-        let event_metadata = json!({"contract_id": &contract_id, "release_info": &release_info});
-        let blob = self.blobs.get(&hash).unwrap_or_else(|| {
-            panic!(
-                "blob doesn't exist for hash: {hash} and version: {}",
-                release_info.version
-            )
+        if let Some(downgrade_release_info) = downgrade_hash
+            .as_ref()
+            .and_then(|downgrade_hash| self.releases.get(downgrade_hash))
+        {
+            require!(
+                downgrade_release_info.family == family,
+                format!(
+                    "downgrade release family: {} doesn't match the release family: {}",
+                    family_name(downgrade_release_info.family.as_ref()),
+                    family_name(family.as_ref())
+                )
+            );
+        }
+
+        let initial_storage_usage = env::storage_usage();
+        let release_info = ReleaseInfo {
+            hash: hash.clone(),
+            version,
+            is_blob_exist: false,
+            downgrade_hash,
+            description,
+            family,
+        };
+
+        event::emit(Event::AddReleaseInfo, &release_info);
+        self.releases.insert(hash.clone(), release_info);
+
+        if is_latest {
+            self.set_latest_release_internal(&hash);
+        }
+
+        let storage_stake = self.storage_stakes.entry(hash.clone()).or_default();
+        storage_stake.release_info = Some(StorageStake {
+            account_id: env::predecessor_account_id(),
+            amount: NearToken::from_yoctonear(0),
         });
+        self.flush_storage();
 
-        event::emit(event, &event_metadata);
-        deployment_info.update(hash, release_info.version.clone());
+        let amount =
+            Self::settle_storage_stake(initial_storage_usage, NearToken::from_yoctonear(0));
+        if let Some(stake) = self
+            .storage_stakes
+            .get_mut(&hash)
+            .and_then(|s| s.release_info.as_mut())
+        {
+            stake.amount = amount;
+        }
+    }
 
-        let args = UpgradeArgs {
-            code: blob.clone(),
-            state_migration_gas,
-        };
+    /// Adds bytes of the contract smart contract to the corresponding release info. The attached
+    /// deposit should cover the storage staking of the blob, the surplus is refunded.
+    #[payable]
+    pub fn add_release_blob(&mut self) {
+        let blob = env::input().unwrap_or_else(|| panic!("no blob's bytes were provided"));
+        let hash = utils::hash_256(&blob);
+        let initial_storage_usage = env::storage_usage();
+        let release_info = self.releases.get_mut(&hash).unwrap_or_else(|| {
+            panic!("release info doesn't exist for the hash: {hash}");
+        });
+        require!(
+            !release_info.is_blob_exist,
+            "blob for the hash is already exist"
+        );
+
+        event::emit(Event::AddBlob, &json!({"blob_hash": &hash}));
 
-        Self::upgrade_promise(contract_id, args, deployment_info)
+        release_info.is_blob_exist = true;
+        self.blobs.insert(hash.clone(), blob);
+        self.stake_blob_storage(
+            &hash,
+            env::predecessor_account_id(),
+            initial_storage_usage,
+            NearToken::from_yoctonear(0),
+        );
     }
 
-    fn upgrade_promise(
-        contract_id: AccountId,
+    /// Starts the chunked upload of the blob for the release with the hash: `hash`. Should be
+    /// used for blobs which don't fit into a single transaction. The attached deposit should
+    /// cover the storage staking of the upload state, the surplus is refunded.
+    #[access_control_any(roles(Role::DAO, Role::Releaser))]
+    #[payable]
+    pub fn begin_release_blob_upload(&mut self, hash: String, total_size: u64, chunks_count: u32) {
+        let initial_storage_usage = env::storage_usage();
+        let release_info = self.releases.get(&hash).unwrap_or_else(|| {
+            panic!("release info doesn't exist for the hash: {hash}");
+        });
+        require!(
+            !release_info.is_blob_exist,
+            "blob for the hash is already exist"
+        );
+        require!(
+            self.blob_uploads.get(&hash).is_none(),
+            "upload of the blob for the hash is already started"
+        );
+        require!(
+            chunks_count > 0 && u64::from(chunks_count) <= total_size,
+            "number of chunks should be between 1 and the total size of the blob"
+        );
+
+        event::emit(
+            Event::BeginBlobUpload,
+            &json!({"blob_hash": &hash, "total_size": total_size, "chunks_count": chunks_count}),
+        );
+
+        self.blob_uploads.insert(
+            hash.clone(),
+            BlobUpload {
+                uploader: env::predecessor_account_id(),
+                total_size,
+                chunks_count,
+                uploaded_chunks: BTreeSet::new(),
+                uploaded_size: 0,
+                deposit: NearToken::from_yoctonear(0),
+            },
+        );
+        self.flush_storage();
+
+        if let Some(upload) = self.blob_uploads.get_mut(&hash) {
+            upload.deposit =
+                Self::settle_storage_stake(initial_storage_usage, NearToken::from_yoctonear(0));
+        }
+    }
+
+    /// Adds the chunk with the index: `index` to the started upload of the blob. The attached
+    /// deposit should cover the storage staking of the chunk, the surplus is refunded.
+    #[access_control_any(roles(Role::DAO, Role::Releaser))]
+    #[payable]
+    pub fn add_release_blob_chunk(&mut self, hash: String, index: u32, data: Base64VecU8) {
+        let initial_storage_usage = env::storage_usage();
+        let upload = self.blob_uploads.get_mut(&hash).unwrap_or_else(|| {
+            panic!("upload of the blob for the hash: {hash} hasn't been started");
+        });
+        require!(
+            upload.uploader == env::predecessor_account_id(),
+            "only the account started the upload can add chunks"
+        );
+        require!(
+            index < upload.chunks_count,
+            format!(
+                "chunk index: {index} is out of range, number of chunks: {}",
+                upload.chunks_count
+            )
+        );
+        require!(
+            !upload.uploaded_chunks.contains(&index),
+            format!("chunk with index: {index} is already uploaded")
+        );
+        require!(!data.0.is_empty(), "chunk couldn't be empty");
+
+        let uploaded_size = u64::try_from(data.0.len())
+            .map(|len| upload.uploaded_size.saturating_add(len))
+            .unwrap_or(u64::MAX);
+        require!(
+            uploaded_size <= upload.total_size,
+            "size of the uploaded chunks exceeds the total size of the blob"
+        );
+
+        upload.uploaded_size = uploaded_size;
+        upload.uploaded_chunks.insert(index);
+        self.blob_chunks.insert((hash.clone(), index), data.0);
+        self.flush_storage();
+
+        if let Some(upload) = self.blob_uploads.get_mut(&hash) {
+            upload.deposit = Self::settle_storage_stake(initial_storage_usage, upload.deposit);
+        }
+    }
+
+    /// Assembles the uploaded chunks into the blob, verifies its hash and adds the blob to the
+    /// corresponding release info. The deposit paid for the chunks is used as the storage staking
+    /// of the blob, the surplus is refunded.
+    #[access_control_any(roles(Role::DAO, Role::Releaser))]
+    #[payable]
+    pub fn finalize_release_blob_upload(&mut self, hash: String) {
+        require!(
+            !env::attached_deposit().is_zero(),
+            "required at least 1 yoctoNEAR"
+        );
+        let initial_storage_usage = env::storage_usage();
+        let upload = self.blob_uploads.remove(&hash).unwrap_or_else(|| {
+            panic!("upload of the blob for the hash: {hash} hasn't been started");
+        });
+        require!(
+            upload.uploader == env::predecessor_account_id(),
+            "only the account started the upload can finalize it"
+        );
+
+        let missing_chunks = upload.missing_chunks();
+        require!(
+            missing_chunks.is_empty(),
+            format!("chunks: {missing_chunks:?} haven't been uploaded yet")
+        );
+        require!(
+            upload.uploaded_size == upload.total_size,
+            "size of the uploaded chunks doesn't match the total size of the blob"
+        );
+
+        let mut blob = Vec::with_capacity(usize::try_from(upload.total_size).unwrap_or_default());
+        for index in 0..upload.chunks_count {
+            let chunk = self
+                .blob_chunks
+                .remove(&(hash.clone(), index))
+                .unwrap_or_else(|| panic!("chunk with index: {index} doesn't exist"));
+            blob.extend_from_slice(&chunk);
+        }
+
+        let blob_hash = utils::hash_256(&blob);
+        require!(
+            blob_hash == hash,
+            format!("hash of the uploaded blob: {blob_hash} doesn't match the expected: {hash}")
+        );
+
+        let release_info = self.releases.get_mut(&hash).unwrap_or_else(|| {
+            panic!("release info doesn't exist for the hash: {hash}");
+        });
+
+        event::emit(Event::AddBlob, &json!({"blob_hash": &hash}));
+
+        release_info.is_blob_exist = true;
+        self.blobs.insert(hash.clone(), blob);
+        self.stake_blob_storage(
+            &hash,
+            upload.uploader,
+            initial_storage_usage,
+            upload.deposit,
+        );
+    }
+
+    /// Cancels the started upload of the blob and removes all uploaded chunks. The deposit paid
+    /// for the chunks is refunded to the account which has started the upload.
+    #[access_control_any(roles(Role::DAO, Role::Releaser))]
+    #[payable]
+    pub fn cancel_release_blob_upload(&mut self, hash: String) {
+        assert_one_yocto();
+        if !self.cancel_blob_upload(&hash) {
+            panic!("upload of the blob for the hash: {hash} hasn't been started");
+        }
+    }
+
+    /// Returns indexes of the chunks which haven't been uploaded yet for the blob with the hash:
+    /// `hash`.
+    #[must_use]
+    pub fn get_missing_blob_chunks(&self, hash: &String) -> Vec<u32> {
+        self.blob_uploads.get(hash).map_or_else(
+            || panic!("upload of the blob for the hash: {hash} hasn't been started"),
+            BlobUpload::missing_chunks,
+        )
+    }
+
+    /// Marks the release with the hash: `hash` as latest in the family of the release.
+    #[access_control_any(roles(Role::DAO, Role::Releaser))]
+    #[payable]
+    pub fn set_latest_release(&mut self, hash: &String) {
+        assert_one_yocto();
+        self.set_latest_release_internal(hash);
+    }
+
+    /// Removes the release info for hash: `hash`. The release referenced by deployments, other
+    /// releases, scheduled upgrades or being the latest one is removed in the force mode only.
+    /// The channels of the release are removed with it, so the release which is the latest in
+    /// the channels with subscribed deployments isn't removed even in the force mode.
+    /// The storage stakes paid for the release info and its blob are returned to the storage
+    /// treasury if it's set or to the accounts which paid for them otherwise. The started upload
+    /// of the blob is cancelled and its deposit is refunded to the account which has started it.
+    #[access_control_any(roles(Role::DAO))]
+    #[payable]
+    pub fn remove_release(&mut self, hash: &String, force: Option<bool>) {
+        assert_one_yocto();
+        let references = self.get_release_references(hash);
+        require!(
+            force.unwrap_or_default() || references.is_empty(),
+            format!("release with hash: {hash} is referenced by {references}")
+        );
+
+        let release_info = self.releases.remove(hash).unwrap_or_else(|| {
+            panic!("release info doesn't exist for hash: {hash}");
+        });
+
+        if references.is_latest {
+            if let Some(family) = &release_info.family {
+                self.family_latest_releases.remove(family);
+            } else {
+                self.latest.remove();
+            }
+        }
+
+        for channel in &references.channels {
+            self.assert_no_channel_subscribers(channel);
+            self.channels.remove(channel);
+        }
+        self.blobs.remove(hash);
+        self.cancel_blob_upload(hash);
+        self.release_upgrade_policies.remove(hash);
+        event::emit(Event::RemoveReleaseInfo, &release_info);
+
+        if let Some(storage_stake) = self.storage_stakes.remove(hash) {
+            for stake in [storage_stake.release_info, storage_stake.blob]
+                .into_iter()
+                .flatten()
+            {
+                self.refund_storage_stake(hash, stake);
+            }
+        }
+    }
+
+    /// Returns everything which references the release with the hash: `hash`.
+    #[must_use]
+    pub fn get_release_references(&self, hash: &String) -> ReleaseReferences {
+        ReleaseReferences {
+            deployments: self
+                .deployments
+                .iter()
+                .filter(|(_, info)| &info.hash == hash)
+                .map(|(contract_id, _)| contract_id.clone())
+                .collect(),
+            is_latest: self.releases.get(hash).is_some_and(|release_info| {
+                self.get_latest_hash(release_info.family.as_ref()).as_ref() == Some(hash)
+            }),
+            downgrade_releases: self
+                .releases
+                .values()
+                .filter(|release_info| release_info.downgrade_hash.as_ref() == Some(hash))
+                .map(|release_info| release_info.hash.clone())
+                .collect(),
+            scheduled_upgrades: self
+                .scheduled_upgrades
+                .iter()
+                .filter(|(_, upgrade)| &upgrade.hash == hash)
+                .map(|(contract_id, _)| contract_id.clone())
+                .collect(),
+            channels: self
+                .channels
+                .iter()
+                .filter(|(_, channel_hash)| *channel_hash == hash)
+                .map(|(channel, _)| channel.clone())
+                .collect(),
+        }
+    }
+
+    /// Sets the account which receives the storage stakes of the removed releases instead of
+    /// the accounts which paid for them.
+    #[access_control_any(roles(Role::DAO))]
+    #[payable]
+    pub fn set_storage_treasury(&mut self, account_id: Option<AccountId>) {
+        assert_one_yocto();
+        event::emit(
+            Event::SetStorageTreasury,
+            &json!({"account_id": &account_id}),
+        );
+        self.storage_treasury = account_id;
+    }
+
+    /// Returns the account which receives the storage stakes of the removed releases.
+    #[must_use]
+    pub fn get_storage_treasury(&self) -> Option<AccountId> {
+        self.storage_treasury.clone()
+    }
+
+    /// Returns the storage stakes paid for the release info and the blob with the hash: `hash`.
+    #[must_use]
+    pub fn get_storage_stake(&self, hash: &String) -> Option<ReleaseStorageStake> {
+        self.storage_stakes.get(hash).cloned()
+    }
+
+    /// Returns a list of existing releases for deployment.
+    #[must_use]
+    pub fn get_releases(&self) -> Vec<ReleaseInfo> {
+        self.releases.values().cloned().collect()
+    }
+
+    /// Returns a page of the releases matched by the filter. The releases are skipped by
+    /// `from_index` and limited by `limit` after filtering.
+    #[must_use]
+    pub fn get_releases_paginated(
+        &self,
+        from_index: Option<u32>,
+        limit: Option<u32>,
+        filter: Option<ReleaseFilter>,
+    ) -> Vec<ReleaseInfo> {
+        let filter = filter.unwrap_or_default();
+        self.releases
+            .values()
+            .filter(|release_info| filter.matches(release_info))
+            .skip(to_usize(from_index.unwrap_or_default()))
+            .take(to_usize(limit.unwrap_or(DEFAULT_PAGE_LIMIT)))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns a number of the releases matched by the filter.
+    #[must_use]
+    pub fn get_releases_count(&self, filter: Option<ReleaseFilter>) -> u32 {
+        filter.map_or_else(
+            || self.releases.len(),
+            |filter| {
+                let count = self
+                    .releases
+                    .values()
+                    .filter(|release_info| filter.matches(release_info))
+                    .count();
+                u32::try_from(count).unwrap_or(u32::MAX)
+            },
+        )
+    }
+
+    /// Returns a hash of the latest release of the default family.
+    #[must_use]
+    pub fn get_latest_release_hash(&self) -> String {
+        self.latest.get().map_or_else(
+            || panic!("the latest release hash hasn't been set yet"),
+            |r| r.hash,
+        )
+    }
+
+    /// Returns the latest release of the family or, if the family isn't provided, of the default
+    /// family.
+    #[must_use]
+    pub fn get_family_latest_release(&self, family: Option<String>) -> Option<ReleaseInfo> {
+        self.get_latest_hash(family.as_ref())
+            .and_then(|hash| self.releases.get(&hash).cloned())
+    }
+
+    /// Marks the release with the hash: `hash` as the latest in the channel. The version of
+    /// the release should be higher than the version of the current release of the channel.
+    #[access_control_any(roles(Role::DAO, Role::Releaser))]
+    #[payable]
+    pub fn set_channel_release(&mut self, channel: String, hash: String) {
+        assert_one_yocto();
+        let release_info = self
+            .releases
+            .get(&hash)
+            .unwrap_or_else(|| panic!("release info doesn't exist for hash: {hash}"));
+
+        if let Some(current_release) = self
+            .channels
+            .get(&channel)
+            .and_then(|current_hash| self.releases.get(current_hash))
+        {
+            require!(
+                current_release.family == release_info.family,
+                format!(
+                    "release family: {} doesn't match the family of the channel: {channel}",
+                    family_name(release_info.family.as_ref())
+                )
+            );
+            require!(
+                current_release.version < release_info.version,
+                format!("version of new release of the channel: {channel} should be higher than previous")
+            );
+        }
+
+        event::emit(
+            Event::SetChannelRelease,
+            &json!({"channel": &channel, "release_info": release_info}),
+        );
+        self.channels.insert(channel, hash);
+    }
+
+    /// Removes the channel which doesn't have subscribed deployments.
+    #[access_control_any(roles(Role::DAO))]
+    #[payable]
+    pub fn remove_channel(&mut self, channel: String) {
+        assert_one_yocto();
+        self.assert_no_channel_subscribers(&channel);
+        require!(
+            self.channels.remove(&channel).is_some(),
+            format!("channel: {channel} doesn't exist")
+        );
+        event::emit(Event::RemoveChannel, &json!({"channel": &channel}));
+    }
+
+    /// Subscribes the contract to the channel or, if the channel isn't provided, to the latest
+    /// release.
+    #[access_control_any(roles(Role::DAO, Role::Updater))]
+    #[payable]
+    pub fn set_deployment_channel(&mut self, contract_id: AccountId, channel: Option<String>) {
+        assert_one_yocto();
+        if let Some(channel) = &channel {
+            require!(
+                self.channels.contains_key(channel),
+                format!("channel: {channel} doesn't exist")
+            );
+        }
+
+        let deployment_info = self.deployments.get_mut(&contract_id).unwrap_or_else(|| {
+            panic!("contract with account id: {contract_id} hasn't been deployed")
+        });
+        event::emit(
+            Event::SetDeploymentChannel,
+            &json!({"contract_id": &contract_id, "channel": &channel}),
+        );
+        deployment_info.channel = channel;
+    }
+
+    /// Returns the latest release of the channel.
+    #[must_use]
+    pub fn get_channel_release(&self, channel: &String) -> Option<ReleaseInfo> {
+        self.channels
+            .get(channel)
+            .and_then(|hash| self.releases.get(hash))
+            .cloned()
+    }
+
+    /// Returns a list of the channels with the hashes of their latest releases.
+    #[must_use]
+    pub fn get_channels(&self) -> BTreeMap<String, String> {
+        self.channels
+            .iter()
+            .map(|(channel, hash)| (channel.clone(), hash.clone()))
+            .collect()
+    }
+
+    /// Deploys a new contract on the release info that corresponds to the provided hash or
+    /// the latest release of the channel. The deployed contract is subscribed to the channel.
+    /// The access keys are added to the account of the contract according to the key policy.
+    #[access_control_any(roles(Role::DAO, Role::Deployer))]
+    #[payable]
+    #[allow(clippy::too_many_arguments)]
+    pub fn deploy(
+        &mut self,
+        new_contract_id: AccountId,
+        init_method: String,
+        init_args: Value,
+        blob_hash: Option<String>,
+        channel: Option<String>,
+        family: Option<String>,
+        options: Option<DeployOptions>,
+    ) -> Promise {
+        let options = options.unwrap_or_default();
+        let target = options.target.unwrap_or_default();
+        let init_args_string = self.resolve_init_args(target, &init_method, &init_args);
+        let (release_info, code) = self
+            .check_deploy(
+                &new_contract_id,
+                blob_hash,
+                channel.as_ref(),
+                family.as_ref(),
+            )
+            .unwrap_or_else(|e| env::panic_str(&e));
+        let event_metadata =
+            json!({"contract_id": &new_contract_id, "release_info": &release_info});
+        let (key_policy, access_keys) = self.resolve_key_policy(target, options.key_policy);
+        let deposit_split =
+            check_deposit_split(target, options.deposit_split, env::attached_deposit(), code)
+                .unwrap_or_else(|e| env::panic_str(&e));
+        let init_call = FunctionCallArgs {
+            function_name: init_method,
+            arguments: init_args_string.clone().into_bytes().into(),
+            amount: deposit_split.init_deposit,
+            gas: init_call_gas(&deposit_split),
+        };
+
+        event::emit(Event::Deploy, &event_metadata);
+
+        let block_time = env::block_timestamp();
+        let deployment_info = DeploymentInfo {
+            hash: release_info.hash.clone(),
+            version: release_info.version.clone(),
+            deployment_time: block_time,
+            upgrade_times: [(block_time, release_info.version.clone())].into(),
+            init_args: init_args_string.clone(),
+            last_error: None,
+            channel,
+            family: release_info.family.clone(),
+            key_policy,
+            access_keys: access_keys.clone(),
+        };
+
+        let promise = match target {
+            DeployTarget::Subaccount => add_function_call(
+                access_keys
+                    .into_iter()
+                    .fold(
+                        Promise::new(new_contract_id.clone()).create_account(),
+                        |promise, key| add_access_key(promise, key, &new_contract_id),
+                    )
+                    .transfer(deposit_split.account_balance)
+                    .deploy_contract(code.clone()),
+                init_call,
+            ),
+            DeployTarget::Existing => upgrade_call(
+                &new_contract_id,
+                UpgradeArgs {
+                    code: code.clone(),
+                    state_migration_gas: None,
+                },
+                &self.get_family_upgrade_method(release_info.family.clone()),
+                None,
+            ),
+            DeployTarget::AccountCreator => {
+                return create_account_call(
+                    self.get_account_creator()
+                        .unwrap_or_else(|| panic!("account creator isn't set")),
+                    &new_contract_id,
+                    access_keys,
+                    code,
+                    deposit_split.account_balance,
+                )
+                .then(
+                    Self::ext(env::current_account_id())
+                        .with_static_gas(
+                            FINISH_ACCOUNT_CREATION_GAS
+                                .saturating_add(init_call.gas)
+                                .saturating_add(FINISH_DEPLOY_GAS),
+                        )
+                        .finish_account_creation(
+                            new_contract_id,
+                            deployment_info,
+                            env::predecessor_account_id(),
+                            env::attached_deposit(),
+                            init_call,
+                        ),
+                );
+            }
+        };
+
+        promise.then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(FINISH_DEPLOY_GAS)
+                .finish_deploy(
+                    new_contract_id,
+                    deployment_info,
+                    target,
+                    env::predecessor_account_id(),
+                    env::attached_deposit(),
+                    refundable_deposit(target, &deposit_split),
+                ),
+        )
+    }
+
+    /// Callback which checks the result of `create_account_advanced` of the account creator and
+    /// calls the init method of the created account. The account creator returns `false` and
+    /// refunds the balance of the account to the controller if the account or its code isn't
+    /// created, so the deployment is recorded as failed with the balance and the deposit of
+    /// the init call, which isn't made, as the refundable deposit.
+    #[private]
+    pub fn finish_account_creation(
+        &mut self,
+        contract_id: AccountId,
+        deployment_info: DeploymentInfo,
+        payer: AccountId,
+        deposit: NearToken,
+        init_call: FunctionCallArgs,
+    ) -> PromiseOrValue<()> {
+        let is_created = match env::promise_result(0) {
+            PromiseResult::Successful(output) => {
+                near_sdk::serde_json::from_slice::<bool>(&output).unwrap_or_default()
+            }
+            PromiseResult::Failed => false,
+        };
+
+        if !is_created {
+            self.record_failed_deployment(
+                contract_id,
+                deployment_info,
+                DeployTarget::AccountCreator,
+                payer,
+                deposit,
+                deposit,
+            );
+            return PromiseOrValue::Value(());
+        }
+
+        let init_deposit = init_call.amount;
+        PromiseOrValue::Promise(
+            add_function_call(Promise::new(contract_id.clone()), init_call).then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(FINISH_DEPLOY_GAS)
+                    .finish_deploy(
+                        contract_id,
+                        deployment_info,
+                        DeployTarget::AccountCreator,
+                        payer,
+                        deposit,
+                        init_deposit,
+                    ),
+            ),
+        )
+    }
+
+    /// Callback which finishes the deployment of the new contract. In case of failure, the
+    /// deployment is recorded as failed with the part of the deposit which is returned to
+    /// the controller. The batch which deploys the contract to the subaccount is atomic, so
+    /// the failure reverts the creation of the account and the whole deposit is returned.
+    /// The account created by the account creator keeps its balance, only the deposit of
+    /// the failed init call is returned. The existing account keeps the attached 1 yoctonear.
+    /// The returned deposit is forwarded to the refund beneficiary or the payer by the follow-up
+    /// call after the refund of the failed receipt.
+    #[private]
+    pub fn finish_deploy(
+        &mut self,
+        contract_id: AccountId,
+        deployment_info: DeploymentInfo,
+        target: DeployTarget,
+        payer: AccountId,
+        deposit: NearToken,
+        refundable_deposit: NearToken,
+    ) {
+        if matches!(env::promise_result(0), PromiseResult::Successful(_)) {
+            self.apply_deployment_result(
+                contract_id.clone(),
+                deployment_info,
+                DeploymentAction::Deploy,
+                true,
+            );
+            self.failed_deployments.remove(&contract_id);
+            return;
+        }
+
+        self.record_failed_deployment(
+            contract_id,
+            deployment_info,
+            target,
+            payer,
+            deposit,
+            refundable_deposit,
+        );
+    }
+
+    /// Callback which forwards the deposit returned to the controller after the failed
+    /// deployment. The deposit stays in the controller if its available balance doesn't cover
+    /// the returned deposits yet and could be refunded later by `refund_failed_deployment`.
+    #[private]
+    pub fn refund_returned_deposit(&mut self, contract_id: AccountId) {
+        let _ = self.refund_deposit_internal(&contract_id);
+    }
+
+    /// Returns the deposit of the failed deployment which has been returned to the controller
+    /// to the refund beneficiary or the payer.
+    #[access_control_any(roles(Role::DAO))]
+    #[payable]
+    pub fn refund_failed_deployment(&mut self, contract_id: AccountId) -> AccountId {
+        assert_one_yocto();
+        self.refund_deposit_internal(&contract_id)
+            .unwrap_or_else(|e| env::panic_str(&e))
+    }
+
+    /// Removes the record of the failed deployment, e.g. after the orphaned account has been
+    /// deleted by the owner of its keys.
+    #[access_control_any(roles(Role::DAO))]
+    #[payable]
+    pub fn remove_failed_deployment(&mut self, contract_id: AccountId) {
+        assert_one_yocto();
+        let failed_deployment = self
+            .failed_deployments
+            .remove(&contract_id)
+            .unwrap_or_else(|| panic!("no failed deployment for: {contract_id}"));
+        event::emit(
+            Event::RemoveFailedDeployment,
+            &json!({"contract_id": contract_id, "failed_deployment": failed_deployment}),
+        );
+    }
+
+    /// Returns the failed deployment of the contract.
+    #[must_use]
+    pub fn get_failed_deployment(&self, contract_id: &AccountId) -> Option<FailedDeployment> {
+        self.failed_deployments.get(contract_id).cloned()
+    }
+
+    /// Returns a list of the failed deployments.
+    #[must_use]
+    pub fn get_failed_deployments(&self) -> BTreeMap<AccountId, FailedDeployment> {
+        self.failed_deployments
+            .iter()
+            .map(|(contract_id, failed_deployment)| {
+                (contract_id.clone(), failed_deployment.clone())
+            })
+            .collect()
+    }
+
+    /// Sets the account which receives the deposits of the failed deployments instead of
+    /// the accounts which paid for them.
+    #[access_control_any(roles(Role::DAO))]
+    #[payable]
+    pub fn set_refund_beneficiary(&mut self, account_id: Option<AccountId>) {
+        assert_one_yocto();
+        event::emit(
+            Event::SetRefundBeneficiary,
+            &json!({"account_id": &account_id}),
+        );
+        self.refund_beneficiary = account_id;
+    }
+
+    /// Returns the account which receives the deposits of the failed deployments.
+    #[must_use]
+    pub fn get_refund_beneficiary(&self) -> Option<AccountId> {
+        self.refund_beneficiary.clone()
+    }
+
+    /// Sets the contract which creates the accounts for the contracts deployed with
+    /// the `account_creator` target, e.g. the `near` or `testnet` linkdrop contract.
+    #[access_control_any(roles(Role::DAO))]
+    #[payable]
+    pub fn set_account_creator(&mut self, account_id: Option<AccountId>) {
+        assert_one_yocto();
+        event::emit(
+            Event::SetAccountCreator,
+            &json!({"account_id": &account_id}),
+        );
+        self.account_creator = account_id;
+    }
+
+    /// Returns the contract which creates the accounts for the contracts deployed with
+    /// the `account_creator` target.
+    #[must_use]
+    pub fn get_account_creator(&self) -> Option<AccountId> {
+        self.account_creator.clone()
+    }
+
+    /// Sets the access keys which are added to the accounts of the contracts deployed with
+    /// the `controller_default` key policy.
+    #[access_control_any(roles(Role::DAO))]
+    #[payable]
+    pub fn set_default_access_keys(&mut self, access_keys: Vec<AccessKeyInfo>) {
+        assert_one_yocto();
+        assert_valid_access_keys(&access_keys);
+        event::emit(Event::SetDefaultAccessKeys, &access_keys);
+        self.default_access_keys = access_keys;
+    }
+
+    /// Returns the access keys which are added to the accounts of the contracts deployed with
+    /// the `controller_default` key policy.
+    #[must_use]
+    pub fn get_default_access_keys(&self) -> Vec<AccessKeyInfo> {
+        self.default_access_keys.clone()
+    }
+
+    /// Adds new deployment info of previously deployed contract.
+    /// E.g. the contract which has been deployed by not this controller contract.
+    #[access_control_any(roles(Role::DAO))]
+    #[payable]
+    pub fn add_deployment_info(&mut self, contract_id: AccountId, deployment_info: DeploymentInfo) {
+        assert_one_yocto();
+        event::emit(
+            Event::AddDeploymentInfo,
+            &json!({"contract_id": contract_id, "deployment_info": deployment_info}),
+        );
+        self.code_drifts.remove(&contract_id);
+        self.deployments.insert(contract_id, deployment_info);
+    }
+
+    /// Callback which adds new deployment info after successful deployment of new contract.
+    /// In case of failure, the error is recorded in the deployment info of the contract.
+    /// Also records the result of the upgrade if it has been started as a part of the batch.
+    #[private]
+    pub fn update_deployment_info(
+        &mut self,
+        contract_id: AccountId,
+        deployment_info: DeploymentInfo,
+        action: DeploymentAction,
+        batch_id: Option<u64>,
+    ) {
+        let is_success = matches!(env::promise_result(0), PromiseResult::Successful(_));
+
+        if let Some(batch_id) = batch_id {
+            self.update_batch_upgrade_status(batch_id, &contract_id, is_success);
+        }
+
+        self.apply_deployment_result(contract_id, deployment_info, action, is_success);
+    }
+
+    /// Returns a list of existing contract deployment infos.
+    #[must_use]
+    pub fn get_deployments(&self) -> BTreeMap<AccountId, DeploymentInfo> {
+        self.deployments
+            .iter()
+            .map(|(acc, info)| (acc.clone(), info.clone()))
+            .collect()
+    }
+
+    /// Returns a page of the contract deployment infos matched by the filter. The deployments
+    /// are skipped by `from_index` and limited by `limit` after filtering.
+    #[must_use]
+    pub fn get_deployments_paginated(
+        &self,
+        from_index: Option<u32>,
+        limit: Option<u32>,
+        filter: Option<DeploymentFilter>,
+    ) -> BTreeMap<AccountId, DeploymentInfo> {
+        let filter = filter.unwrap_or_default();
+        self.deployments
+            .iter()
+            .filter(|(_, info)| filter.matches(info))
+            .skip(to_usize(from_index.unwrap_or_default()))
+            .take(to_usize(limit.unwrap_or(DEFAULT_PAGE_LIMIT)))
+            .map(|(acc, info)| (acc.clone(), info.clone()))
+            .collect()
+    }
+
+    /// Returns a number of the contract deployments matched by the filter.
+    #[must_use]
+    pub fn get_deployments_count(&self, filter: Option<DeploymentFilter>) -> u32 {
+        filter.map_or_else(
+            || self.deployments.len(),
+            |filter| {
+                let count = self
+                    .deployments
+                    .values()
+                    .filter(|info| filter.matches(info))
+                    .count();
+                u32::try_from(count).unwrap_or(u32::MAX)
+            },
+        )
+    }
+
+    /// Returns a contract deployment info for corresponding account id.
+    #[must_use]
+    pub fn get_deployment(&self, account_id: &AccountId) -> Option<DeploymentInfo> {
+        self.deployments.get(account_id).cloned()
+    }
+
+    /// Compares the actual code hashes of the contracts encoded in Base58, as returned by
+    /// the `view_account` RPC method, with the hashes recorded in the deployment infos.
+    /// Mismatched deployments are flagged as drifted until the hashes match again or the contract
+    /// is redeployed by the controller. Returns the drifted contracts among the reported ones.
+    #[access_control_any(roles(Role::DAO, Role::Updater))]
+    #[payable]
+    pub fn report_code_hashes(
+        &mut self,
+        code_hashes: BTreeMap<AccountId, String>,
+    ) -> Vec<AccountId> {
+        assert_one_yocto();
+        let mut drifted = vec![];
+
+        for (contract_id, code_hash) in code_hashes {
+            let actual_hash =
+                utils::code_hash_to_hex(&code_hash).unwrap_or_else(|e| env::panic_str(&e));
+            let expected_hash = self
+                .deployments
+                .get(&contract_id)
+                .map(|info| info.hash.clone())
+                .unwrap_or_else(|| {
+                    panic!("contract with account id: {contract_id} hasn't been deployed")
+                });
+
+            if actual_hash == expected_hash {
+                self.code_drifts.remove(&contract_id);
+                continue;
+            }
+
+            let is_new_drift = self
+                .code_drifts
+                .get(&contract_id)
+                .is_none_or(|drift| drift.actual_hash != actual_hash);
+
+            if is_new_drift {
+                let drift = CodeDrift {
+                    expected_hash,
+                    actual_hash,
+                    detected_at: env::block_timestamp(),
+                };
+                event::emit(
+                    Event::CodeHashDrift,
+                    &json!({"contract_id": &contract_id, "drift": &drift}),
+                );
+                self.code_drifts.insert(contract_id.clone(), drift);
+            }
+
+            drifted.push(contract_id);
+        }
+
+        drifted
+    }
+
+    /// Returns a page of the deployments which code differs from the recorded one.
+    #[must_use]
+    pub fn get_drifted_deployments(
+        &self,
+        from_index: Option<u32>,
+        limit: Option<u32>,
+    ) -> BTreeMap<AccountId, CodeDrift> {
+        self.code_drifts
+            .iter()
+            .skip(to_usize(from_index.unwrap_or_default()))
+            .take(to_usize(limit.unwrap_or(DEFAULT_PAGE_LIMIT)))
+            .map(|(contract_id, drift)| (contract_id.clone(), drift.clone()))
+            .collect()
+    }
+
+    /// Upgrades a contract with account id and provided hash or the latest hash of the channel
+    /// of the contract.
+    #[access_control_any(roles(Role::DAO, Role::Updater))]
+    #[payable]
+    pub fn upgrade(
+        &mut self,
+        contract_id: AccountId,
+        hash: Option<String>,
+        state_migration_gas: Option<u64>,
+    ) -> Promise {
+        assert_one_yocto();
+        self.assert_not_time_locked(&contract_id);
+
+        self.upgrade_internal(
+            contract_id,
+            hash,
+            false,
+            state_migration_gas,
+            Event::Upgrade,
+        )
+    }
+
+    /// Upgrades a contract with account id and provided hash without checking version.
+    #[access_control_any(roles(Role::DAO))]
+    #[payable]
+    pub fn unrestricted_upgrade(
+        &mut self,
+        contract_id: AccountId,
+        hash: String,
+        state_migration_gas: Option<u64>,
+    ) -> Promise {
+        assert_one_yocto();
+        self.assert_not_time_locked(&contract_id);
+        self.assert_no_proposal_policy();
+
+        self.upgrade_internal(
+            contract_id,
+            Some(hash),
+            true,
+            state_migration_gas,
+            Event::UnrestrictedUpgrade,
+        )
+    }
+
+    /// Sets the method used for upgrading the contracts of the family. If the method isn't
+    /// provided, the contracts are upgraded by the `upgrade` method of Aurora Engine.
+    #[access_control_any(roles(Role::DAO))]
+    #[payable]
+    pub fn set_family_upgrade_method(
+        &mut self,
+        family: Option<String>,
+        method: Option<UpgradeMethod>,
+    ) {
+        assert_one_yocto();
+        if let Some(method) = &method {
+            assert_valid_upgrade_method(method);
+        }
+
+        event::emit(
+            Event::SetFamilyUpgradeMethod,
+            &json!({"family": family_name(family.as_ref()), "method": &method}),
+        );
+
+        if let Some(method) = method {
+            self.family_upgrade_methods.insert(family, method);
+        } else {
+            self.family_upgrade_methods.remove(&family);
+        }
+    }
+
+    /// Overrides the method used for upgrading the contract. If the method isn't provided,
+    /// the method of the contract family is used.
+    #[access_control_any(roles(Role::DAO))]
+    #[payable]
+    pub fn set_deployment_upgrade_method(
+        &mut self,
+        contract_id: AccountId,
+        method: Option<UpgradeMethod>,
+    ) {
+        assert_one_yocto();
+        require!(
+            self.deployments.contains_key(&contract_id),
+            format!("contract with account id: {contract_id} hasn't been deployed")
+        );
+        if let Some(method) = &method {
+            assert_valid_upgrade_method(method);
+        }
+
+        event::emit(
+            Event::SetDeploymentUpgradeMethod,
+            &json!({"contract_id": &contract_id, "method": &method}),
+        );
+
+        if let Some(method) = method {
+            self.deployment_upgrade_methods.insert(contract_id, method);
+        } else {
+            self.deployment_upgrade_methods.remove(&contract_id);
+        }
+    }
+
+    /// Returns the method used for upgrading the contracts of the family.
+    #[must_use]
+    pub fn get_family_upgrade_method(&self, family: Option<String>) -> UpgradeMethod {
+        self.family_upgrade_methods
+            .get(&family)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Returns the method used for upgrading the contract.
+    #[must_use]
+    pub fn get_upgrade_method(&self, contract_id: &AccountId) -> UpgradeMethod {
+        self.deployment_upgrade_methods
+            .get(contract_id)
+            .cloned()
+            .unwrap_or_else(|| {
+                let family = self
+                    .deployments
+                    .get(contract_id)
+                    .and_then(|info| info.family.clone());
+                self.get_family_upgrade_method(family)
+            })
+    }
+
+    /// Sets the compatibility policy for upgrading the contracts of the family. The policy of
+    /// the release takes precedence over the policy of the family.
+    #[access_control_any(roles(Role::DAO))]
+    #[payable]
+    pub fn set_family_upgrade_policy(
+        &mut self,
+        family: Option<String>,
+        policy: Option<UpgradePolicy>,
+    ) {
+        assert_one_yocto();
+        event::emit(
+            Event::SetUpgradePolicy,
+            &json!({"family": family_name(family.as_ref()), "policy": &policy}),
+        );
+
+        if let Some(policy) = policy {
+            self.family_upgrade_policies.insert(family, policy);
+        } else {
+            self.family_upgrade_policies.remove(&family);
+        }
+    }
+
+    /// Sets the compatibility policy for upgrading the contracts to the release.
+    #[access_control_any(roles(Role::DAO))]
+    #[payable]
+    pub fn set_release_upgrade_policy(&mut self, hash: String, policy: Option<UpgradePolicy>) {
+        assert_one_yocto();
+        require!(
+            self.releases.contains_key(&hash),
+            format!("release info doesn't exist for hash: {hash}")
+        );
+        event::emit(
+            Event::SetUpgradePolicy,
+            &json!({"hash": &hash, "policy": &policy}),
+        );
+
+        if let Some(policy) = policy {
+            self.release_upgrade_policies.insert(hash, policy);
+        } else {
+            self.release_upgrade_policies.remove(&hash);
+        }
+    }
+
+    /// Returns the compatibility policy for upgrading the contracts of the family.
+    #[must_use]
+    pub fn get_family_upgrade_policy(&self, family: Option<String>) -> Option<UpgradePolicy> {
+        self.family_upgrade_policies.get(&family).cloned()
+    }
+
+    /// Returns the compatibility policy for upgrading the contracts to the release. The policy of
+    /// the family of the release is returned if the release doesn't have its own policy.
+    #[must_use]
+    pub fn get_upgrade_policy(&self, hash: &String) -> Option<UpgradePolicy> {
+        self.release_upgrade_policies
+            .get(hash)
+            .cloned()
+            .or_else(|| {
+                self.releases
+                    .get(hash)
+                    .and_then(|release_info| self.family_upgrade_policies.get(&release_info.family))
+                    .cloned()
+            })
+    }
+
+    /// Marks the contracts of the family as production ones or not. The production contracts
+    /// aren't upgraded to pre-release versions if the upgrade policy forbids them. The contracts
+    /// are production ones if the flag isn't set.
+    #[access_control_any(roles(Role::DAO))]
+    #[payable]
+    pub fn set_family_production(&mut self, family: Option<String>, is_production: Option<bool>) {
+        assert_one_yocto();
+        event::emit(
+            Event::SetProductionFlag,
+            &json!({"family": family_name(family.as_ref()), "is_production": is_production}),
+        );
+
+        if let Some(is_production) = is_production {
+            self.family_production_flags.insert(family, is_production);
+        } else {
+            self.family_production_flags.remove(&family);
+        }
+    }
+
+    /// Marks the contract as a production one or not. The flag of the contract takes precedence
+    /// over the flag of its family.
+    #[access_control_any(roles(Role::DAO))]
+    #[payable]
+    pub fn set_deployment_production(
+        &mut self,
+        contract_id: AccountId,
+        is_production: Option<bool>,
+    ) {
+        assert_one_yocto();
+        require!(
+            self.deployments.contains_key(&contract_id),
+            format!("contract with account id: {contract_id} hasn't been deployed")
+        );
+        event::emit(
+            Event::SetProductionFlag,
+            &json!({"contract_id": &contract_id, "is_production": is_production}),
+        );
+
+        if let Some(is_production) = is_production {
+            self.deployment_production_flags
+                .insert(contract_id, is_production);
+        } else {
+            self.deployment_production_flags.remove(&contract_id);
+        }
+    }
+
+    /// Checks whether the contract is a production one according to its own flag or the flag of
+    /// its family.
+    #[must_use]
+    pub fn is_production(&self, contract_id: &AccountId) -> bool {
+        self.deployment_production_flags
+            .get(contract_id)
+            .or_else(|| {
+                self.deployments
+                    .get(contract_id)
+                    .and_then(|info| self.family_production_flags.get(&info.family))
+            })
+            .copied()
+            .unwrap_or(true)
+    }
+
+    /// Sets the delay in nanoseconds between the announcement and the execution of the upgrades of
+    /// the contract. The contract with the delay could be upgraded by the scheduled upgrades only.
+    /// Downgrades, including the rollbacks of the rollout waves, aren't time-locked, so a broken
+    /// release could be reverted at once.
+    #[access_control_any(roles(Role::DAO))]
+    #[payable]
+    pub fn set_upgrade_delay(&mut self, contract_id: AccountId, delay: Option<u64>) {
+        assert_one_yocto();
+        require!(
+            self.deployments.contains_key(&contract_id),
+            format!("contract with account id: {contract_id} hasn't been deployed")
+        );
+        event::emit(
+            Event::SetUpgradeDelay,
+            &json!({"contract_id": &contract_id, "delay": delay}),
+        );
+
+        if let Some(delay) = delay {
+            self.upgrade_delays.insert(contract_id, delay);
+        } else {
+            self.upgrade_delays.remove(&contract_id);
+        }
+    }
+
+    /// Returns the delay in nanoseconds between the announcement and the execution of
+    /// the upgrades of the contract.
+    #[must_use]
+    pub fn get_upgrade_delay(&self, contract_id: &AccountId) -> Option<u64> {
+        self.upgrade_delays.get(contract_id).copied()
+    }
+
+    /// Announces the upgrade of the contract to the provided or the latest hash. The upgrade could
+    /// be executed by `execute_scheduled_upgrade` after the upgrade delay of the contract.
+    #[access_control_any(roles(Role::DAO, Role::Updater))]
+    #[payable]
+    pub fn schedule_upgrade(
+        &mut self,
+        contract_id: AccountId,
+        hash: Option<String>,
+        state_migration_gas: Option<u64>,
+        is_unrestricted: Option<bool>,
+    ) -> ScheduledUpgrade {
+        assert_one_yocto();
+        let is_unrestricted = is_unrestricted.unwrap_or_default();
+        require!(
+            !is_unrestricted || self.acl_has_role(Role::DAO.into(), env::predecessor_account_id()),
+            "unrestricted upgrade could be scheduled by DAO only"
+        );
+
+        if is_unrestricted {
+            self.assert_no_proposal_policy();
+        }
+        require!(
+            !self.scheduled_upgrades.contains_key(&contract_id),
+            format!("upgrade of the contract: {contract_id} is already scheduled")
+        );
+
+        let hash = self
+            .resolve_upgrade_hash(&contract_id, hash)
+            .unwrap_or_else(|e| env::panic_str(&e));
+        let release_info = self
+            .releases
+            .get(&hash)
+            .unwrap_or_else(|| panic!("no release info for hash: {hash}"));
+        require!(
+            release_info.is_blob_exist,
+            format!("blob doesn't exist for hash: {hash}")
+        );
+        let deployment_info = self.deployments.get(&contract_id).unwrap_or_else(|| {
+            panic!("contract with account id: {contract_id} hasn't been deployed")
+        });
+        deployment_info
+            .check_family(release_info)
+            .unwrap_or_else(|e| env::panic_str(&e));
+        require!(
+            is_unrestricted || release_info.version > deployment_info.version,
+            format!(
+                "upgradable version: {} should be higher than the deployed version: {}",
+                release_info.version, deployment_info.version
+            )
+        );
+
+        let announced_at = env::block_timestamp();
+        let delay = self.get_upgrade_delay(&contract_id).unwrap_or_default();
+        let scheduled_upgrade = ScheduledUpgrade {
+            hash,
+            state_migration_gas,
+            is_unrestricted,
+            announced_at,
+            execute_after: announced_at.saturating_add(delay),
+        };
+
+        event::emit(
+            Event::ScheduleUpgrade,
+            &json!({"contract_id": &contract_id, "scheduled_upgrade": &scheduled_upgrade}),
+        );
+        self.scheduled_upgrades
+            .insert(contract_id, scheduled_upgrade.clone());
+
+        scheduled_upgrade
+    }
+
+    /// Cancels the scheduled upgrade of the contract.
+    #[access_control_any(roles(Role::DAO))]
+    #[payable]
+    pub fn cancel_scheduled_upgrade(&mut self, contract_id: AccountId) {
+        assert_one_yocto();
+        let scheduled_upgrade = self
+            .scheduled_upgrades
+            .remove(&contract_id)
+            .unwrap_or_else(|| panic!("upgrade of the contract: {contract_id} isn't scheduled"));
+        event::emit(
+            Event::CancelScheduledUpgrade,
+            &json!({"contract_id": &contract_id, "scheduled_upgrade": &scheduled_upgrade}),
+        );
+    }
+
+    /// Executes the scheduled upgrade of the contract after the announcement window.
+    #[access_control_any(roles(Role::DAO, Role::Updater))]
+    #[payable]
+    pub fn execute_scheduled_upgrade(&mut self, contract_id: AccountId) -> Promise {
+        assert_one_yocto();
+        let scheduled_upgrade = self
+            .scheduled_upgrades
+            .get(&contract_id)
+            .cloned()
+            .unwrap_or_else(|| panic!("upgrade of the contract: {contract_id} isn't scheduled"));
+        require!(
+            env::block_timestamp() >= scheduled_upgrade.execute_after,
+            format!(
+                "upgrade of the contract: {contract_id} could be executed after: {}",
+                scheduled_upgrade.execute_after
+            )
+        );
+        self.scheduled_upgrades.remove(&contract_id);
+
+        let event = if scheduled_upgrade.is_unrestricted {
+            self.assert_no_proposal_policy();
+            Event::UnrestrictedUpgrade
+        } else {
+            Event::Upgrade
+        };
+
+        self.upgrade_internal(
+            contract_id,
+            Some(scheduled_upgrade.hash),
+            scheduled_upgrade.is_unrestricted,
+            scheduled_upgrade.state_migration_gas,
+            event,
+        )
+    }
+
+    /// Returns the scheduled upgrade of the contract.
+    #[must_use]
+    pub fn get_scheduled_upgrade(&self, contract_id: &AccountId) -> Option<ScheduledUpgrade> {
+        self.scheduled_upgrades.get(contract_id).cloned()
+    }
+
+    /// Returns a list of all scheduled upgrades.
+    #[must_use]
+    pub fn get_scheduled_upgrades(&self) -> BTreeMap<AccountId, ScheduledUpgrade> {
+        self.scheduled_upgrades
+            .iter()
+            .map(|(contract_id, upgrade)| (contract_id.clone(), upgrade.clone()))
+            .collect()
+    }
+
+    /// Upgrades the contracts with provided account ids or, if they aren't provided, the contracts
+    /// matched by the filter to the provided or the latest hash. Returns id of the batch which
+    /// could be used to track the results of the upgrades. The upgrades which don't fit into the
+    /// attached gas stay pending and could be started by `resume_batch_upgrade`. If the hash isn't
+    /// provided, all contracts should have the same latest release according to their channels.
+    ///
+    /// Each upgrade gets `upgrade_gas` or, if it isn't provided, the gas of a single upgrade:
+    /// 180 TGas or 130 TGas plus `state_migration_gas` (and 70 TGas more for staging the code
+    /// by `near-plugins`). Every upgrade additionally reserves 15 TGas for the callback and
+    /// the scheduling, which includes copying the blob into the promise, and 15 TGas is reserved
+    /// for the batch itself. So a call with 300 TGas starts `285 / (upgrade gas + 15)` upgrades:
+    /// one with the default gas and five with `upgrade_gas` of 40 TGas.
+    #[access_control_any(roles(Role::DAO, Role::Updater))]
+    #[payable]
+    pub fn batch_upgrade(
+        &mut self,
+        contract_ids: Option<Vec<AccountId>>,
+        filter: Option<DeploymentFilter>,
+        hash: Option<String>,
+        state_migration_gas: Option<u64>,
+        upgrade_gas: Option<Gas>,
+    ) -> u64 {
+        assert_one_yocto();
+        assert_valid_upgrade_gas(state_migration_gas, upgrade_gas);
+        let contract_ids = contract_ids.unwrap_or_else(|| self.select_deployments(filter));
+        require!(!contract_ids.is_empty(), "no contracts to upgrade");
+
+        let hash = hash.unwrap_or_else(|| self.resolve_batch_upgrade_hash(&contract_ids));
+        require!(
+            self.releases.get(&hash).is_some(),
+            format!("no release info for hash: {hash}")
+        );
+
+        self.create_batch_upgrade(contract_ids, hash, state_migration_gas, upgrade_gas, false)
+    }
+
+    /// Starts the pending upgrades of the batch which haven't fit into the gas of the previous
+    /// transactions.
+    #[access_control_any(roles(Role::DAO, Role::Updater))]
+    #[payable]
+    pub fn resume_batch_upgrade(&mut self, batch_id: u64) {
+        assert_one_yocto();
+        self.dispatch_batch_upgrade(batch_id);
+    }
+
+    /// Removes the completed batch upgrade.
+    #[access_control_any(roles(Role::DAO))]
+    #[payable]
+    pub fn remove_batch_upgrade(&mut self, batch_id: u64) {
+        assert_one_yocto();
+        let batch = self
+            .batch_upgrades
+            .get(&batch_id)
+            .unwrap_or_else(|| panic!("batch upgrade with id: {batch_id} doesn't exist"));
+        require!(
+            batch.is_completed(),
+            "batch upgrade hasn't been completed yet"
+        );
+        self.batch_upgrades.remove(&batch_id);
+    }
+
+    /// Returns the batch upgrade with the results of the upgrades.
+    #[must_use]
+    pub fn get_batch_upgrade(&self, batch_id: u64) -> Option<BatchUpgrade> {
+        self.batch_upgrades.get(&batch_id).cloned()
+    }
+
+    /// Creates a staged rollout of the provided or the latest release. The waves are upgraded one
+    /// by one with `advance_rollout`. The contracts of the percentage waves are selected from the
+    /// deployed contracts with lower versions which aren't listed explicitly in other waves.
+    /// If the hash isn't provided, the latest release is rolled out to the contracts without
+    /// a channel only. The `upgrade_gas` is attached to each upgrade of the waves as in
+    /// `batch_upgrade`.
+    #[access_control_any(roles(Role::DAO, Role::Updater))]
+    #[payable]
+    pub fn create_rollout(
+        &mut self,
+        hash: Option<String>,
+        waves: Vec<WaveSelection>,
+        state_migration_gas: Option<u64>,
+        upgrade_gas: Option<Gas>,
+    ) -> u64 {
+        assert_one_yocto();
+        assert_valid_upgrade_gas(state_migration_gas, upgrade_gas);
+        require!(
+            !waves.is_empty(),
+            "rollout should contain at least one wave"
+        );
+        let is_default_hash = hash.is_none();
+        let hash = hash
+            .or_else(|| self.latest.get().map(|r| r.hash))
+            .unwrap_or_else(|| panic!("no latest nor custom hash was provided for upgrading"));
+        let release_info = self
+            .releases
+            .get(&hash)
+            .unwrap_or_else(|| panic!("no release info for hash: {hash}"));
+        require!(
+            release_info.is_blob_exist,
+            format!("blob doesn't exist for hash: {hash}")
+        );
+
+        let waves = self.select_wave_contracts(release_info, waves, is_default_hash);
+        let rollout_id = self.next_rollout_id;
+        self.next_rollout_id += 1;
+
+        let rollout = Rollout {
+            hash,
+            state_migration_gas,
+            upgrade_gas,
+            created_at: env::block_timestamp(),
+            waves: waves
+                .into_iter()
+                .map(|contract_ids| RolloutWave {
+                    contract_ids,
+                    batch_id: None,
+                    previous_hashes: BTreeMap::new(),
+                    rollback_batch_ids: vec![],
+                })
+                .collect(),
+        };
+
+        event::emit(
+            Event::CreateRollout,
+            &json!({"rollout_id": rollout_id, "rollout": &rollout}),
+        );
+        self.rollouts.insert(rollout_id, rollout);
+
+        rollout_id
+    }
+
+    /// Starts upgrading of the next wave of the rollout. The wave isn't started if the previous
+    /// wave hasn't been completed or has any failed upgrade. Returns id of the batch upgrade
+    /// of the wave.
+    #[access_control_any(roles(Role::DAO, Role::Updater))]
+    #[payable]
+    pub fn advance_rollout(&mut self, rollout_id: u64) -> u64 {
+        assert_one_yocto();
+        let mut rollout = self
+            .rollouts
+            .get(&rollout_id)
+            .cloned()
+            .unwrap_or_else(|| panic!("rollout with id: {rollout_id} doesn't exist"));
+        require!(!rollout.is_rolled_back(), "rollout has been rolled back");
+
+        if let Some(wave) = rollout.current_wave() {
+            let batch = self.get_wave_batch(wave);
+            require!(
+                batch.is_completed(),
+                "current wave of the rollout hasn't been completed yet"
+            );
+            require!(
+                batch.is_succeeded(),
+                "current wave of the rollout has failed upgrades"
+            );
+        }
+
+        let index = rollout
+            .next_wave()
+            .unwrap_or_else(|| panic!("rollout doesn't have waves to advance"));
+        let wave = &mut rollout.waves[index];
+        wave.previous_hashes = wave
+            .contract_ids
+            .iter()
+            .filter_map(|contract_id| {
+                self.deployments
+                    .get(contract_id)
+                    .map(|info| (contract_id.clone(), info.hash.clone()))
+            })
+            .collect();
+
+        let batch_id = self.create_batch_upgrade(
+            wave.contract_ids.clone(),
+            rollout.hash.clone(),
+            rollout.state_migration_gas,
+            rollout.upgrade_gas,
+            false,
+        );
+        wave.batch_id = Some(batch_id);
+
+        event::emit(
+            Event::AdvanceRollout,
+            &json!({"rollout_id": rollout_id, "wave": index, "batch_id": batch_id}),
+        );
+        self.rollouts.insert(rollout_id, rollout);
+
+        batch_id
+    }
+
+    /// Downgrades the successfully upgraded contracts of the current wave of the rollout to
+    /// the hashes they had before the wave. Returns ids of the batches of the downgrades.
+    #[access_control_any(roles(Role::DAO, Role::Updater, Role::Downgrader))]
+    #[payable]
+    pub fn rollback_rollout_wave(&mut self, rollout_id: u64) -> Vec<u64> {
+        assert_one_yocto();
+        let mut rollout = self
+            .rollouts
+            .get(&rollout_id)
+            .cloned()
+            .unwrap_or_else(|| panic!("rollout with id: {rollout_id} doesn't exist"));
+        require!(!rollout.is_rolled_back(), "rollout has been rolled back");
+
+        let index = rollout
+            .waves
+            .iter()
+            .rposition(|wave| wave.batch_id.is_some())
+            .unwrap_or_else(|| panic!("rollout doesn't have started waves"));
+        let upgrade_gas = rollout.upgrade_gas;
+        let wave = &mut rollout.waves[index];
+        let batch = self.get_wave_batch(wave);
+        require!(
+            batch.is_completed(),
+            "current wave of the rollout hasn't been completed yet"
+        );
+
+        let mut targets = BTreeMap::<String, Vec<AccountId>>::new();
+
+        for (contract_id, status) in &batch.targets {
+            if let (UpgradeStatus::Succeeded, Some(hash)) =
+                (status, wave.previous_hashes.get(contract_id))
+            {
+                targets
+                    .entry(hash.clone())
+                    .or_default()
+                    .push(contract_id.clone());
+            }
+        }
+
+        require!(
+            !targets.is_empty(),
+            "current wave of the rollout doesn't have upgraded contracts"
+        );
+
+        wave.rollback_batch_ids = targets
+            .into_iter()
+            .map(|(hash, contract_ids)| {
+                self.create_batch_upgrade(contract_ids, hash, None, upgrade_gas, true)
+            })
+            .collect();
+
+        event::emit(
+            Event::RollbackRolloutWave,
+            &json!({"rollout_id": rollout_id, "wave": index, "batch_ids": &wave.rollback_batch_ids}),
+        );
+        let batch_ids = wave.rollback_batch_ids.clone();
+        self.rollouts.insert(rollout_id, rollout);
+
+        batch_ids
+    }
+
+    /// Removes the rollout.
+    #[access_control_any(roles(Role::DAO))]
+    #[payable]
+    pub fn remove_rollout(&mut self, rollout_id: u64) {
+        assert_one_yocto();
+        require!(
+            self.rollouts.remove(&rollout_id).is_some(),
+            format!("rollout with id: {rollout_id} doesn't exist")
+        );
+    }
+
+    /// Returns the rollout with its waves.
+    #[must_use]
+    pub fn get_rollout(&self, rollout_id: u64) -> Option<Rollout> {
+        self.rollouts.get(&rollout_id).cloned()
+    }
+
+    /// Downgrades the contract with account id to the release with the hash: `target_hash` or,
+    /// if it isn't provided, to the downgrade hash of the deployed release. The target release
+    /// should be in the downgrade chain of the deployed release or have a lower version.
+    #[access_control_any(roles(Role::DAO, Role::Downgrader))]
+    #[payable]
+    pub fn downgrade(
+        &mut self,
+        contract_id: AccountId,
+        target_hash: Option<String>,
+        state_migration_gas: Option<u64>,
+    ) -> Promise {
+        assert_one_yocto();
+        let target_hash = self
+            .resolve_downgrade_hash(&contract_id, target_hash)
+            .unwrap_or_else(|e| env::panic_str(&e));
+
+        self.try_upgrade(
+            contract_id,
+            target_hash,
+            true,
+            state_migration_gas,
+            Event::Downgrade,
+            None,
+            None,
+        )
+        .unwrap_or_else(|e| env::panic_str(&e))
+    }
+
+    /// Returns the releases which the release with the hash: `hash` could be downgraded to by
+    /// following the downgrade hashes, starting from the nearest one.
+    #[must_use]
+    pub fn get_downgrade_chain(&self, hash: &String) -> Vec<ReleaseInfo> {
+        let mut chain = vec![];
+        let mut visited = BTreeSet::from([hash.clone()]);
+        let mut next_hash = self
+            .releases
+            .get(hash)
+            .and_then(|r| r.downgrade_hash.clone());
+
+        while let Some(release_info) = next_hash
+            .take()
+            .filter(|downgrade_hash| visited.insert(downgrade_hash.clone()))
+            .and_then(|downgrade_hash| self.releases.get(&downgrade_hash))
+        {
+            next_hash.clone_from(&release_info.downgrade_hash);
+            chain.push(release_info.clone());
+        }
+
+        chain
+    }
+
+    /// Runs the same checks as `deploy` with the same options without creating promises and
+    /// returns the plan of the deployment or the reasons why the deployment would fail.
+    /// The required deposit is the sum of the deposit split if it's provided. The access control
+    /// isn't checked.
+    #[must_use]
+    pub fn preflight_deploy(
+        &self,
+        new_contract_id: AccountId,
+        blob_hash: Option<String>,
+        channel: Option<String>,
+        family: Option<String>,
+        options: Option<DeployOptions>,
+    ) -> Preflight {
+        let options = options.unwrap_or_default();
+        let target = options.target.unwrap_or_default();
+        let (release_info, code) = match self.check_deploy(
+            &new_contract_id,
+            blob_hash,
+            channel.as_ref(),
+            family.as_ref(),
+        ) {
+            Ok(result) => result,
+            Err(reason) => {
+                return Preflight::Rejected {
+                    reasons: vec![reason],
+                }
+            }
+        };
+
+        let mut reasons = vec![];
+        if target == DeployTarget::Existing && options.key_policy.is_some() {
+            reasons.push("key policy isn't supported for existing accounts".to_string());
+        }
+        if target == DeployTarget::AccountCreator && self.get_account_creator().is_none() {
+            reasons.push("account creator isn't set".to_string());
+        }
+        let required_deposit =
+            required_deploy_deposit(target, options.deposit_split.as_ref(), code);
+        let deposit_split =
+            check_deposit_split(target, options.deposit_split, required_deposit, code)
+                .map_err(|reason| reasons.push(reason))
+                .ok();
+
+        match deposit_split {
+            Some(deposit_split) if reasons.is_empty() => Preflight::Ready(DeploymentPlan {
+                action: DeploymentAction::Deploy,
+                contract_id: new_contract_id,
+                attached_gas: deploy_gas(
+                    target,
+                    &self.get_family_upgrade_method(release_info.family.clone()),
+                    &deposit_split,
+                ),
+                release_info: release_info.clone(),
+                current_version: None,
+                required_deposit,
+            }),
+            _ => Preflight::Rejected { reasons },
+        }
+    }
+
+    /// Runs the same checks as `upgrade` without creating promises and returns the plan of
+    /// the upgrade or the reasons why the upgrade would fail. The access control isn't checked.
+    #[must_use]
+    pub fn preflight_upgrade(
+        &self,
+        contract_id: AccountId,
+        hash: Option<String>,
+        state_migration_gas: Option<u64>,
+    ) -> Preflight {
+        let reasons = self.check_not_time_locked(&contract_id).err();
+        let hash = self.resolve_upgrade_hash(&contract_id, hash);
+
+        self.preflight_upgrade_internal(
+            contract_id,
+            hash,
+            false,
+            state_migration_gas,
+            DeploymentAction::Upgrade,
+            reasons.into_iter().collect(),
+        )
+    }
+
+    /// Runs the same checks as `downgrade` without creating promises and returns the plan of
+    /// the downgrade or the reasons why the downgrade would fail. The access control isn't
+    /// checked.
+    #[must_use]
+    pub fn preflight_downgrade(
+        &self,
+        contract_id: AccountId,
+        target_hash: Option<String>,
+        state_migration_gas: Option<u64>,
+    ) -> Preflight {
+        let hash = self.resolve_downgrade_hash(&contract_id, target_hash);
+
+        self.preflight_upgrade_internal(
+            contract_id,
+            hash,
+            true,
+            state_migration_gas,
+            DeploymentAction::Downgrade,
+            vec![],
+        )
+    }
+}
+
+impl AuroraControllerFactory {
+    /// Returns the hash of the latest release of the channel or the hash of the latest release
+    /// if the channel isn't provided.
+    fn get_default_hash(
+        &self,
+        channel: Option<&String>,
+        family: Option<&String>,
+    ) -> Option<String> {
+        channel.map_or_else(
+            || self.get_latest_hash(family),
+            |channel| self.channels.get(channel).cloned(),
+        )
+    }
+
+    /// Returns the hash of the latest release of the family.
+    fn get_latest_hash(&self, family: Option<&String>) -> Option<String> {
+        family.map_or_else(
+            || self.latest.get().map(|r| r.hash),
+            |family| self.family_latest_releases.get(family).cloned(),
+        )
+    }
+
+    /// Returns the default hash for upgrading the contract according to its channel.
+    fn get_deployment_default_hash(&self, contract_id: &AccountId) -> Option<String> {
+        let info = self.deployments.get(contract_id)?;
+        self.get_default_hash(info.channel.as_ref(), info.family.as_ref())
+    }
+
+    fn set_latest_release_internal(&mut self, hash: &String) {
+        let new_latest = self.releases.get(hash).unwrap_or_else(|| {
+            panic!("release info doesn't exist for hash: {hash}");
+        });
+
+        if let Some(current_latest) = self
+            .get_latest_hash(new_latest.family.as_ref())
+            .and_then(|current_hash| self.releases.get(&current_hash))
+        {
+            assert!(
+                current_latest.version < new_latest.version,
+                "version of new latest should be higher than previous"
+            );
+        }
+
+        if let Some(family) = &new_latest.family {
+            self.family_latest_releases
+                .insert(family.clone(), hash.clone());
+        } else {
+            self.latest.set(new_latest);
+        }
+        event::emit(Event::SetLatestReleaseInfo, new_latest);
+    }
+
+    /// Records the storage stake of the blob with the hash: `hash` paid by `account_id`. The stake
+    /// includes `staked` amount and the cost of the storage used since `initial_storage_usage`.
+    fn stake_blob_storage(
+        &mut self,
+        hash: &String,
+        account_id: AccountId,
+        initial_storage_usage: u64,
+        staked: NearToken,
+    ) {
+        let storage_stake = self.storage_stakes.entry(hash.clone()).or_default();
+        storage_stake.blob = Some(StorageStake {
+            account_id,
+            amount: staked,
+        });
+        self.flush_storage();
+
+        let amount = Self::settle_storage_stake(initial_storage_usage, staked);
+        if let Some(stake) = self
+            .storage_stakes
+            .get_mut(hash)
+            .and_then(|s| s.blob.as_mut())
+        {
+            stake.amount = amount;
+        }
+    }
+
+    /// Returns the storage stake which includes `staked` amount and the cost of the storage used
+    /// since `initial_storage_usage`. The surplus of the attached deposit or the stake released
+    /// by the freed storage is refunded to the predecessor.
+    fn settle_storage_stake(initial_storage_usage: u64, staked: NearToken) -> NearToken {
+        let storage_usage = env::storage_usage();
+        let storage_byte_cost = env::storage_byte_cost();
+        let deposit = env::attached_deposit();
+
+        let (stake, refund) = if storage_usage >= initial_storage_usage {
+            let cost =
+                storage_byte_cost.saturating_mul(u128::from(storage_usage - initial_storage_usage));
+            let refund = deposit.checked_sub(cost).unwrap_or_else(|| {
+                panic!("attached deposit: {deposit} doesn't cover the storage cost: {cost}")
+            });
+            (staked.saturating_add(cost), refund)
+        } else {
+            let released = storage_byte_cost
+                .saturating_mul(u128::from(initial_storage_usage - storage_usage))
+                .min(staked);
+            (
+                staked.saturating_sub(released),
+                deposit.saturating_add(released),
+            )
+        };
+
+        if !refund.is_zero() {
+            Promise::new(env::predecessor_account_id()).transfer(refund);
+        }
+
+        stake
+    }
+
+    fn refund_storage_stake(&self, hash: &String, stake: StorageStake) {
+        let receiver_id = self.storage_treasury.clone().unwrap_or(stake.account_id);
+
+        event::emit(
+            Event::RefundStorageStake,
+            &json!({"blob_hash": hash, "receiver_id": &receiver_id, "amount": stake.amount}),
+        );
+
+        if !stake.amount.is_zero() {
+            Promise::new(receiver_id).transfer(stake.amount);
+        }
+    }
+
+    /// Removes the started upload of the blob with all uploaded chunks and refunds the deposit
+    /// paid for them to the account which has started the upload. Returns `false` if the upload
+    /// hasn't been started.
+    fn cancel_blob_upload(&mut self, hash: &String) -> bool {
+        let Some(upload) = self.blob_uploads.remove(hash) else {
+            return false;
+        };
+
+        for index in upload.uploaded_chunks {
+            self.blob_chunks.remove(&(hash.clone(), index));
+        }
+
+        event::emit(Event::CancelBlobUpload, &json!({"blob_hash": hash}));
+
+        if !upload.deposit.is_zero() {
+            Promise::new(upload.uploader).transfer(upload.deposit);
+        }
+
+        true
+    }
+
+    fn update_batch_upgrade_status(
+        &mut self,
+        batch_id: u64,
+        contract_id: &AccountId,
+        is_success: bool,
+    ) {
+        let Some(batch) = self.batch_upgrades.get_mut(&batch_id) else {
+            return;
+        };
+        let status = if is_success {
+            UpgradeStatus::Succeeded
+        } else {
+            UpgradeStatus::Failed("upgrade promise has failed".to_string())
+        };
+
+        batch.targets.insert(contract_id.clone(), status);
+
+        if batch.is_completed() {
+            event::emit(
+                Event::BatchUpgradeCompleted,
+                &json!({"batch_id": batch_id, "batch": batch}),
+            );
+        }
+    }
+
+    fn update_fleet_pause_status(
+        &mut self,
+        fleet_pause_id: u64,
+        contract_id: AccountId,
+        status: PauseStatus,
+    ) {
+        let Some(fleet_pause) = self.fleet_pauses.get_mut(&fleet_pause_id) else {
+            return;
+        };
+
+        fleet_pause.targets.insert(contract_id, status);
+
+        if fleet_pause.is_completed() {
+            event::emit(
+                Event::FleetPauseCompleted,
+                &json!({"fleet_pause_id": fleet_pause_id, "fleet_pause": fleet_pause}),
+            );
+        }
+    }
+
+    /// Writes all cached changes of the collections to the storage, so the storage usage could be
+    /// measured. The latest release is written at once, so it doesn't need to be flushed.
+    fn flush_storage(&mut self) {
+        self.releases.flush();
+        self.family_latest_releases.flush();
+        self.blobs.flush();
+        self.blob_uploads.flush();
+        self.blob_chunks.flush();
+        self.storage_stakes.flush();
+    }
+
+    fn upgrade_internal(
+        &self,
+        contract_id: AccountId,
+        hash: Option<String>,
+        skip_version_check: bool,
+        state_migration_gas: Option<u64>,
+        event: Event,
+    ) -> Promise {
+        let hash = self
+            .resolve_upgrade_hash(&contract_id, hash)
+            .unwrap_or_else(|e| env::panic_str(&e));
+
+        self.try_upgrade(
+            contract_id,
+            hash,
+            skip_version_check,
+            state_migration_gas,
+            event,
+            None,
+            None,
+        )
+        .unwrap_or_else(|e| env::panic_str(&e))
+    }
+
+    /// Checks that the contract with account id: `contract_id` could be upgraded to the release
+    /// with the hash: `hash` and creates the upgrade promise. Returns the reason of the failure
+    /// instead of panicking, so it could be used for upgrading a batch of contracts. The event of
+    /// the violated upgrade policy is kept only by the batch upgrades, which don't panic; the
+    /// single upgrades report the violation in the panic message only.
+    #[allow(clippy::too_many_arguments)]
+    fn try_upgrade(
+        &self,
+        contract_id: AccountId,
+        hash: String,
+        skip_version_check: bool,
+        state_migration_gas: Option<u64>,
+        event: Event,
+        batch_id: Option<u64>,
+        upgrade_gas: Option<Gas>,
+    ) -> Result<Promise, String> {
+        let (release_info, deployment_info) =
+            self.check_upgrade(&contract_id, &hash, skip_version_check)?;
+
+        if !skip_version_check {
+            if let Err(violation) =
+                self.check_upgrade_policy(&contract_id, release_info, deployment_info)
+            {
+                event::emit(
+                    Event::UpgradePolicyViolation,
+                    &json!({"contract_id": &contract_id, "hash": &hash, "violation": &violation}),
+                );
+                return Err(violation.to_string());
+            }
+        }
+
+        let event_metadata = json!({"contract_id": &contract_id, "release_info": &release_info});
+        let blob = self.get_release_blob(release_info)?;
+        let mut deployment_info = deployment_info.clone();
+
+        let action = if matches!(event, Event::Downgrade) {
+            DeploymentAction::Downgrade
+        } else {
+            DeploymentAction::Upgrade
+        };
+        event::emit(event, &event_metadata);
+        deployment_info.update(hash, release_info.version.clone());
+
+        let args = UpgradeArgs {
+            code: blob.clone(),
+            state_migration_gas,
+        };
+
+        let method = self.get_upgrade_method(&contract_id);
+
+        Ok(Self::upgrade_promise(
+            contract_id,
+            args,
+            &method,
+            deployment_info,
+            action,
+            batch_id,
+            upgrade_gas,
+        ))
+    }
+
+    /// Checks the deposit and the init call of the deployment to the target and returns the init
+    /// arguments recorded in the deployment info. The code of the existing account is replaced
+    /// by its upgrade method without the init call, so only DAO could deploy to such accounts.
+    fn resolve_init_args(
+        &self,
+        target: DeployTarget,
+        init_method: &str,
+        init_args: &Value,
+    ) -> String {
+        if target == DeployTarget::Existing {
+            assert_one_yocto();
+            require!(
+                self.acl_has_role(Role::DAO.into(), env::predecessor_account_id()),
+                "only DAO could deploy to existing accounts"
+            );
+            require!(
+                init_method.is_empty()
+                    && (init_args.is_null() || init_args.as_object().is_some_and(|o| o.is_empty())),
+                "init method and arguments aren't supported for existing accounts"
+            );
+            return String::new();
+        }
+
+        require!(
+            !env::attached_deposit().is_zero(),
+            "required at least 1 yoctonear"
+        );
+        near_sdk::serde_json::to_string(init_args)
+            .unwrap_or_else(|e| panic!("bad format of the init args: {e}"))
+    }
+
+    /// Returns the key policy of the new contract with the access keys which are added to
+    /// the account by the policy. The default keys of the controller are used if the policy
+    /// isn't provided, or the full access key of the signer as before if the default keys
+    /// aren't set. The keys aren't added to the existing accounts.
+    fn resolve_key_policy(
+        &self,
+        target: DeployTarget,
+        key_policy: Option<KeyPolicy>,
+    ) -> (Option<KeyPolicy>, Vec<AccessKeyInfo>) {
+        if target == DeployTarget::Existing {
+            require!(
+                key_policy.is_none(),
+                "key policy isn't supported for existing accounts"
+            );
+            return (None, vec![]);
+        }
+
+        let key_policy = key_policy.unwrap_or_else(|| {
+            if self.default_access_keys.is_empty() {
+                KeyPolicy::Signer
+            } else {
+                KeyPolicy::ControllerDefault
+            }
+        });
+        let access_keys = match &key_policy {
+            KeyPolicy::Signer => vec![AccessKeyInfo {
+                public_key: env::signer_account_pk(),
+                permission: KeyPermission::FullAccess,
+            }],
+            KeyPolicy::Locked => vec![],
+            KeyPolicy::Keys(keys) => {
+                assert_valid_access_keys(keys);
+                keys.clone()
+            }
+            KeyPolicy::ControllerDefault => {
+                require!(
+                    !self.default_access_keys.is_empty(),
+                    "default access keys of the controller aren't set"
+                );
+                self.default_access_keys.clone()
+            }
+        };
+
+        (Some(key_policy), access_keys)
+    }
+
+    /// Checks that the contract with account id: `contract_id` could be upgraded to the release
+    /// with the hash: `hash` without taking into account the upgrade policy.
+    fn check_upgrade(
+        &self,
+        contract_id: &AccountId,
+        hash: &String,
+        skip_version_check: bool,
+    ) -> Result<(&ReleaseInfo, &DeploymentInfo), String> {
+        let release_info = self
+            .releases
+            .get(hash)
+            .ok_or_else(|| format!("no release info for hash: {hash}"))?;
+        let deployment_info = self.deployments.get(contract_id).ok_or_else(|| {
+            format!("contract with account id: {contract_id} hasn't been deployed")
+        })?;
+
+        deployment_info.check_family(release_info)?;
+
+        if release_info.version <= deployment_info.version && !skip_version_check {
+            return Err(format!(
+                "upgradable version: {} should be higher than the deployed version: {}",
+                release_info.version, deployment_info.version
+            ));
+        }
+
+        Ok((release_info, deployment_info))
+    }
+
+    /// Checks the upgrade of the deployed contract to the release against the upgrade policy of
+    /// the release.
+    fn check_upgrade_policy(
+        &self,
+        contract_id: &AccountId,
+        release_info: &ReleaseInfo,
+        deployment_info: &DeploymentInfo,
+    ) -> Result<(), PolicyViolation> {
+        self.get_upgrade_policy(&release_info.hash)
+            .map_or(Ok(()), |policy| {
+                policy.check(
+                    &deployment_info.version,
+                    &release_info.version,
+                    self.is_production(contract_id),
+                )
+            })
+    }
+
+    /// Returns the blob of the release.
+    fn get_release_blob(&self, release_info: &ReleaseInfo) -> Result<&Vec<u8>, String> {
+        self.blobs.get(&release_info.hash).ok_or_else(|| {
+            format!(
+                "blob doesn't exist for hash: {} and version: {}",
+                release_info.hash, release_info.version
+            )
+        })
+    }
+
+    /// Checks that the new contract could be deployed and returns the release info and the blob
+    /// of the release to deploy.
+    fn check_deploy(
+        &self,
+        new_contract_id: &AccountId,
+        blob_hash: Option<String>,
+        channel: Option<&String>,
+        family: Option<&String>,
+    ) -> Result<(&ReleaseInfo, &Vec<u8>), String> {
+        // Check that the `new_contract_id` wasn't used for another contract before.
+        if self.deployments.contains_key(new_contract_id) {
+            return Err(format!("{new_contract_id} is already deployed"));
+        }
+        // The record of the failed deployment is replaced by the new deployment, so the returned
+        // deposit should be refunded before.
+        if self
+            .failed_deployments
+            .get(new_contract_id)
+            .is_some_and(|failed_deployment| {
+                failed_deployment.refunded_to.is_none()
+                    && !failed_deployment.refundable_deposit.is_zero()
+            })
+        {
+            return Err(format!(
+                "deposit of the failed deployment of {new_contract_id} isn't refunded yet"
+            ));
+        }
+
+        if let Some(channel) = channel {
+            if !self.channels.contains_key(channel) {
+                return Err(format!("channel: {channel} doesn't exist"));
+            }
+        }
+
+        let blob_hash = blob_hash
+            .or_else(|| self.get_default_hash(channel, family))
+            .ok_or("no custom hash nor the latest was provided")?;
+        let release_info = self
+            .releases
+            .get(&blob_hash)
+            .ok_or_else(|| format!("no release info for hash: {blob_hash}"))?;
+
+        if let Some(family) = family {
+            if release_info.family.as_ref() != Some(family) {
+                return Err(format!(
+                    "release family: {} doesn't match the requested family: {family}",
+                    family_name(release_info.family.as_ref())
+                ));
+            }
+        }
+
+        let code = self
+            .blobs
+            .get(&blob_hash)
+            .ok_or_else(|| format!("blob doesn't exist for hash: {blob_hash}"))?;
+
+        Ok((release_info, code))
+    }
+
+    /// Returns the provided hash or the default hash for upgrading the contract.
+    fn resolve_upgrade_hash(
+        &self,
+        contract_id: &AccountId,
+        hash: Option<String>,
+    ) -> Result<String, String> {
+        hash.or_else(|| self.get_deployment_default_hash(contract_id))
+            .ok_or_else(|| "no latest nor custom hash was provided for upgrading".to_string())
+    }
+
+    /// Returns the hash of the release which the contract is downgraded to. The target release
+    /// should be in the downgrade chain of the deployed release or have a lower version.
+    fn resolve_downgrade_hash(
+        &self,
+        contract_id: &AccountId,
+        target_hash: Option<String>,
+    ) -> Result<String, String> {
+        let deployment_info = self.deployments.get(contract_id).ok_or_else(|| {
+            format!("contract with account id: {contract_id} hasn't been deployed")
+        })?;
+        let release_info = self.releases.get(&deployment_info.hash).ok_or_else(|| {
+            format!(
+                "release info doesn't exist for hash: {}",
+                &deployment_info.hash
+            )
+        })?;
+
+        let Some(target_hash) = target_hash else {
+            return release_info
+                .downgrade_hash
+                .clone()
+                .ok_or_else(|| "release info doesn't include downgrade hash".to_string());
+        };
+
+        let is_in_chain = self
+            .get_downgrade_chain(&release_info.hash)
+            .iter()
+            .any(|r| r.hash == target_hash);
+
+        if !is_in_chain {
+            let target_release_info = self
+                .releases
+                .get(&target_hash)
+                .ok_or_else(|| format!("no release info for hash: {target_hash}"))?;
+
+            if target_release_info.version >= deployment_info.version {
+                return Err(format!(
+                    "downgrade version: {} should be lower than the deployed version: {}",
+                    target_release_info.version, deployment_info.version
+                ));
+            }
+        }
+
+        Ok(target_hash)
+    }
+
+    /// Runs the checks of the upgrade or downgrade of the contract without creating promises.
+    fn preflight_upgrade_internal(
+        &self,
+        contract_id: AccountId,
+        hash: Result<String, String>,
+        skip_version_check: bool,
+        state_migration_gas: Option<u64>,
+        action: DeploymentAction,
+        mut reasons: Vec<String>,
+    ) -> Preflight {
+        let checked = hash.and_then(|hash| {
+            let (release_info, deployment_info) =
+                self.check_upgrade(&contract_id, &hash, skip_version_check)?;
+
+            if !skip_version_check {
+                self.check_upgrade_policy(&contract_id, release_info, deployment_info)
+                    .map_err(|violation| violation.to_string())?;
+            }
+
+            Ok((release_info, deployment_info))
+        });
+
+        match checked {
+            Ok((release_info, deployment_info)) => {
+                if let Err(reason) = self.get_release_blob(release_info) {
+                    reasons.push(reason);
+                }
+
+                if reasons.is_empty() {
+                    let method = self.get_upgrade_method(&contract_id);
+
+                    return Preflight::Ready(DeploymentPlan {
+                        action,
+                        contract_id,
+                        release_info: release_info.clone(),
+                        current_version: Some(deployment_info.version.clone()),
+                        required_deposit: NearToken::from_yoctonear(1),
+                        attached_gas: upgrade_method_gas(&method, state_migration_gas, None)
+                            .saturating_add(ADD_DEPLOYMENT_GAS),
+                    });
+                }
+            }
+            Err(reason) => reasons.push(reason),
+        }
+
+        Preflight::Rejected { reasons }
+    }
+
+    /// Stores the deployment info of the contract after the successful action or records
+    /// the failure otherwise.
+    fn apply_deployment_result(
+        &mut self,
+        contract_id: AccountId,
+        deployment_info: DeploymentInfo,
+        action: DeploymentAction,
+        is_success: bool,
+    ) {
+        if is_success {
+            let deployment_info = DeploymentInfo {
+                last_error: None,
+                ..deployment_info
+            };
+            event::emit(
+                Event::UpdateDeploymentInfo,
+                &json!({"contract_id": contract_id, "deployment_info": deployment_info}),
+            );
+            self.code_drifts.remove(&contract_id);
+            self.deployments.insert(contract_id, deployment_info);
+        } else {
+            self.record_deployment_failure(contract_id, deployment_info, action);
+        }
+    }
+
+    /// Records the failed deployment with the part of the deposit returned to the controller and
+    /// schedules the refund of the returned deposit.
+    fn record_failed_deployment(
+        &mut self,
+        contract_id: AccountId,
+        deployment_info: DeploymentInfo,
+        target: DeployTarget,
+        payer: AccountId,
+        deposit: NearToken,
+        refundable_deposit: NearToken,
+    ) {
+        let failed_deployment = FailedDeployment {
+            target,
+            payer,
+            hash: deployment_info.hash,
+            version: deployment_info.version,
+            deposit,
+            refundable_deposit,
+            refunded_to: None,
+            timestamp: env::block_timestamp(),
+        };
+        event::emit(
+            Event::DeploymentFailure,
+            &json!({"contract_id": &contract_id, "failed_deployment": &failed_deployment}),
+        );
+        self.failed_deployments
+            .insert(contract_id.clone(), failed_deployment);
+
+        if !refundable_deposit.is_zero() {
+            Self::ext(env::current_account_id())
+                .with_static_gas(REFUND_DEPOSIT_GAS)
+                .refund_returned_deposit(contract_id);
+        }
+    }
+
+    /// Transfers the deposit of the failed deployment which has been returned to the controller
+    /// to the refund beneficiary or the payer. The deposit is transferred only if the balance of
+    /// the controller which isn't locked for the storage covers all the returned deposits which
+    /// haven't been refunded yet, so the deposits of other deployments aren't spent.
+    fn refund_deposit_internal(&mut self, contract_id: &AccountId) -> Result<AccountId, String> {
+        let failed_deployment = self
+            .failed_deployments
+            .get(contract_id)
+            .ok_or_else(|| format!("no failed deployment for: {contract_id}"))?;
+
+        if let Some(receiver_id) = &failed_deployment.refunded_to {
+            return Err(format!(
+                "deposit of {contract_id} is already refunded to {receiver_id}"
+            ));
+        }
+
+        let deposit = failed_deployment.refundable_deposit;
+        if deposit.is_zero() {
+            return Err(format!(
+                "deposit of {contract_id} isn't returned to the controller"
+            ));
+        }
+
+        let receiver_id = self
+            .refund_beneficiary
+            .clone()
+            .unwrap_or_else(|| failed_deployment.payer.clone());
+        let pending_deposits = self
+            .failed_deployments
+            .values()
+            .filter(|failed_deployment| failed_deployment.refunded_to.is_none())
+            .fold(NearToken::from_yoctonear(0), |sum, failed_deployment| {
+                sum.saturating_add(failed_deployment.refundable_deposit)
+            });
+
+        self.failed_deployments.flush();
+        let storage_cost =
+            env::storage_byte_cost().saturating_mul(u128::from(env::storage_usage()));
+        let available_balance = env::account_balance().saturating_sub(storage_cost);
+
+        if available_balance < pending_deposits {
+            return Err(format!(
+                "available balance: {} doesn't cover the returned deposits: {}",
+                available_balance.as_yoctonear(),
+                pending_deposits.as_yoctonear()
+            ));
+        }
+
+        event::emit(
+            Event::RefundDeployDeposit,
+            &json!({"contract_id": contract_id, "receiver_id": &receiver_id, "amount": deposit}),
+        );
+
+        if let Some(failed_deployment) = self.failed_deployments.get_mut(contract_id) {
+            failed_deployment.refunded_to = Some(receiver_id.clone());
+        }
+        Promise::new(receiver_id.clone()).transfer(deposit);
+
+        Ok(receiver_id)
+    }
+
+    /// Records the failed upgrade or downgrade in the deployment info of the contract.
+    fn record_deployment_failure(
+        &mut self,
+        contract_id: AccountId,
+        deployment_info: DeploymentInfo,
+        action: DeploymentAction,
+    ) {
+        let error = DeploymentError {
+            action,
+            hash: deployment_info.hash.clone(),
+            version: deployment_info.version.clone(),
+            timestamp: env::block_timestamp(),
+        };
+        event::emit(
+            Event::DeploymentFailure,
+            &json!({"contract_id": contract_id, "error": error}),
+        );
+
+        if let Some(stored_info) = self.deployments.get_mut(&contract_id) {
+            stored_info.last_error = Some(error);
+        }
+    }
+
+    fn upgrade_promise(
+        contract_id: AccountId,
         args: UpgradeArgs,
+        method: &UpgradeMethod,
         deployment_info: DeploymentInfo,
+        action: DeploymentAction,
+        batch_id: Option<u64>,
+        upgrade_gas: Option<Gas>,
+    ) -> Promise {
+        upgrade_call(&contract_id, args, method, upgrade_gas).then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(ADD_DEPLOYMENT_GAS)
+                .with_unused_gas_weight(0)
+                .update_deployment_info(contract_id, deployment_info, action, batch_id),
+        )
+    }
+
+    fn attach_full_access_key_internal(public_key: PublicKey) -> Promise {
+        event::emit(
+            Event::AttachFullAccessKey,
+            &json!({"public_key": &public_key}),
+        );
+        Promise::new(env::current_account_id()).add_full_access_key(public_key)
+    }
+
+    /// Checks that the receiver is a deployed contract and the actions are allowed by its
+    /// delegation policy.
+    fn check_delegated_actions(
+        &self,
+        receiver_id: &AccountId,
+        actions: &[FunctionCallArgs],
+    ) -> Result<(), String> {
+        if !self.deployments.contains_key(receiver_id) {
+            return Err(format!("receiver: {receiver_id} isn't a deployed contract"));
+        }
+
+        let policy = self
+            .get_delegation_policy(receiver_id)
+            .ok_or_else(|| format!("no delegation policy for the receiver: {receiver_id}"))?;
+
+        actions
+            .iter()
+            .try_for_each(|action| policy.check(action))
+            .map_err(|e| format!("{e} for the receiver: {receiver_id}"))
+    }
+
+    fn delegate_execution_internal(
+        &mut self,
+        receiver_id: AccountId,
+        actions: Vec<FunctionCallArgs>,
+        deposit: NearToken,
+    ) -> (Promise, u64) {
+        let log_actions = actions
+            .iter()
+            .map(LogFunctionCallArgs::from)
+            .collect::<Vec<_>>();
+        let method_names = actions
+            .iter()
+            .map(|action| action.function_name.clone())
+            .collect();
+        let execution_id = self.start_execution(&receiver_id, method_names);
+
+        event::emit(
+            Event::DelegatedExecution,
+            &json!({
+                "execution_id": execution_id,
+                "receiver_id": &receiver_id,
+                "actions": log_actions,
+            }),
+        );
+
+        let mut total = deposit;
+        let calls = actions
+            .into_iter()
+            .fold(Promise::new(receiver_id), |promise, action| {
+                total = total
+                    .checked_sub(action.amount)
+                    .unwrap_or_else(|| env::panic_str("not enough deposit attached"));
+                promise.function_call(
+                    action.function_name,
+                    action.arguments.into(),
+                    action.amount,
+                    action.gas,
+                )
+            });
+
+        (calls, execution_id)
+    }
+
+    /// Returns the state with the empty collections and the unset settings.
+    fn empty_state() -> Self {
+        Self {
+            releases: IterableMap::new(keys::Prefix::Releases),
+            blobs: IterableMap::new(keys::Prefix::Blobs),
+            deployments: IterableMap::new(keys::Prefix::Deployments),
+            latest: LazyOption::new(keys::Prefix::LatestRelease, None),
+            blob_uploads: LookupMap::new(keys::Prefix::BlobUploads),
+            blob_chunks: LookupMap::new(keys::Prefix::BlobChunks),
+            storage_stakes: LookupMap::new(keys::Prefix::StorageStakes),
+            storage_treasury: None,
+            batch_upgrades: IterableMap::new(keys::Prefix::BatchUpgrades),
+            next_batch_id: 0,
+            rollouts: IterableMap::new(keys::Prefix::Rollouts),
+            next_rollout_id: 0,
+            upgrade_delays: LookupMap::new(keys::Prefix::UpgradeDelays),
+            scheduled_upgrades: IterableMap::new(keys::Prefix::ScheduledUpgrades),
+            proposal_policy: None,
+            proposals: IterableMap::new(keys::Prefix::Proposals),
+            next_proposal_id: 0,
+            channels: IterableMap::new(keys::Prefix::Channels),
+            family_upgrade_methods: LookupMap::new(keys::Prefix::FamilyUpgradeMethods),
+            deployment_upgrade_methods: LookupMap::new(keys::Prefix::DeploymentUpgradeMethods),
+            family_upgrade_policies: LookupMap::new(keys::Prefix::FamilyUpgradePolicies),
+            release_upgrade_policies: LookupMap::new(keys::Prefix::ReleaseUpgradePolicies),
+            code_drifts: IterableMap::new(keys::Prefix::CodeDrifts),
+            default_access_keys: vec![],
+            account_creator: None,
+            failed_deployments: IterableMap::new(keys::Prefix::FailedDeployments),
+            refund_beneficiary: None,
+            family_pause_methods: LookupMap::new(keys::Prefix::FamilyPauseMethods),
+            deployment_pause_methods: LookupMap::new(keys::Prefix::DeploymentPauseMethods),
+            fleet_pauses: IterableMap::new(keys::Prefix::FleetPauses),
+            next_fleet_pause_id: 0,
+            deployment_fleet_pauses: LookupMap::new(keys::Prefix::DeploymentFleetPauses),
+            family_delegation_policies: LookupMap::new(keys::Prefix::FamilyDelegationPolicies),
+            deployment_delegation_policies: LookupMap::new(
+                keys::Prefix::DeploymentDelegationPolicies,
+            ),
+            executions: LookupMap::new(keys::Prefix::Executions),
+            next_execution_id: 0,
+            family_latest_releases: LookupMap::new(keys::Prefix::FamilyLatestReleases),
+            family_production_flags: LookupMap::new(keys::Prefix::FamilyProductionFlags),
+            deployment_production_flags: LookupMap::new(keys::Prefix::DeploymentProductionFlags),
+        }
+    }
+
+    /// Records the start of the delegated execution in the history and returns its id.
+    /// The oldest execution is removed from the history if it exceeds the limit.
+    fn start_execution(&mut self, receiver_id: &AccountId, method_names: Vec<String>) -> u64 {
+        let execution_id = self.next_execution_id;
+        self.next_execution_id += 1;
+
+        if let Some(expired_id) = execution_id.checked_sub(MAX_EXECUTION_HISTORY) {
+            self.executions.remove(&expired_id);
+        }
+
+        self.executions.insert(
+            execution_id,
+            ExecutionRecord {
+                receiver_id: receiver_id.clone(),
+                method_names,
+                started_at: env::block_timestamp(),
+                status: ExecutionStatus::InProgress,
+            },
+        );
+
+        execution_id
+    }
+
+    /// Records the result of the delegated execution by the result of the promise with
+    /// the index: `result_idx` and returns the value returned by the receiver if it has
+    /// succeeded.
+    fn finish_execution(&mut self, execution_id: u64, result_idx: u64) -> Option<Vec<u8>> {
+        match env::promise_result(result_idx) {
+            PromiseResult::Successful(output) => {
+                self.set_execution_status(
+                    execution_id,
+                    ExecutionStatus::Succeeded {
+                        output: logged_bytes(&output, "output"),
+                    },
+                );
+                Some(output)
+            }
+            PromiseResult::Failed => {
+                self.set_execution_status(execution_id, ExecutionStatus::Failed);
+                None
+            }
+        }
+    }
+
+    fn set_execution_status(&mut self, execution_id: u64, status: ExecutionStatus) {
+        event::emit(
+            Event::DelegatedExecutionCompleted,
+            &json!({"execution_id": execution_id, "status": &status}),
+        );
+
+        if let Some(record) = self.executions.get_mut(&execution_id) {
+            record.status = status;
+        }
+    }
+
+    /// Records the result of the step of the delegated execution plan and returns it.
+    fn finish_step(
+        &mut self,
+        execution_id: u64,
+        receiver_id: AccountId,
+        result_idx: u64,
+    ) -> ExecutionStepResult {
+        let output = self.finish_execution(execution_id, result_idx);
+
+        ExecutionStepResult {
+            execution_id,
+            receiver_id,
+            status: if output.is_some() {
+                ExecutionStepStatus::Succeeded
+            } else {
+                ExecutionStepStatus::Failed
+            },
+            output: output.map(|output| logged_bytes(&output, "output")),
+        }
+    }
+
+    fn execution_callback(execution_id: u64) -> Promise {
+        Self::ext(env::current_account_id())
+            .with_static_gas(DELEGATED_EXECUTION_CALLBACK_GAS)
+            .with_unused_gas_weight(0)
+            .finish_delegated_execution(execution_id)
+    }
+
+    /// Executes the step of the sequential execution plan with the callback which starts
+    /// the remaining steps.
+    fn execute_step(
+        caller: AccountId,
+        execution_ids: Vec<u64>,
+        step: ExecutionStep,
+        steps: Vec<ExecutionStep>,
+        results: Vec<ExecutionStepResult>,
     ) -> Promise {
-        ext_aurora::ext(contract_id.clone())
-            .with_static_gas(
-                args.state_migration_gas
-                    .map_or(UPGRADE_GAS_NO_MIGRATION_GAS, |gas| {
-                        UPGRADE_GAS.saturating_add(Gas::from_gas(gas))
-                    }),
+        let receiver_id = step.receiver_id.clone();
+        let callback_gas = steps
+            .iter()
+            .flat_map(|next_step| &next_step.actions)
+            .fold(EXECUTION_STEP_CALLBACK_GAS, |gas, action| {
+                gas.saturating_add(action.gas)
+            })
+            .saturating_add(
+                EXECUTION_STEP_CALLBACK_GAS
+                    .saturating_mul(u64::try_from(steps.len()).unwrap_or(u64::MAX)),
+            );
+
+        step_promise(step).then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(callback_gas)
+                .with_unused_gas_weight(0)
+                .finish_execution_step(caller, execution_ids, receiver_id, steps, results),
+        )
+    }
+
+    fn set_proposal_policy_internal(&mut self, policy: Option<ProposalPolicy>) {
+        if let Some(policy) = &policy {
+            self.assert_valid_proposal_policy(policy);
+        }
+
+        event::emit(Event::SetProposalPolicy, &json!({"policy": &policy}));
+        self.proposal_policy = policy;
+    }
+
+    /// Checks that the proposals of the policy could be approved by the current members of
+    /// the roles, so the policy couldn't lock the sensitive actions.
+    fn assert_valid_proposal_policy(&self, policy: &ProposalPolicy) {
+        require!(
+            !policy.roles.is_empty(),
+            "proposal policy should contain at least one role"
+        );
+        require!(
+            policy.threshold > 0,
+            "threshold of the proposal policy should be greater than zero"
+        );
+
+        let threshold = u64::from(policy.threshold);
+        let members = policy
+            .roles
+            .iter()
+            .flat_map(|role| self.acl_get_grantees((*role).into(), 0, threshold))
+            .collect::<BTreeSet<_>>();
+        require!(
+            u64::try_from(members.len()).unwrap_or(u64::MAX) >= threshold,
+            format!(
+                "threshold: {} of the proposal policy exceeds the number of the members: {}",
+                policy.threshold,
+                members.len()
+            )
+        );
+    }
+
+    /// Checks that the sensitive actions could be executed without the proposals.
+    fn assert_no_proposal_policy(&self) {
+        require!(
+            self.proposal_policy.is_none(),
+            "the action could be executed by the approved proposal only"
+        );
+    }
+
+    /// Checks that the predecessor is a member of the roles from the proposal policy and returns
+    /// the policy.
+    fn assert_proposal_member(&self) -> ProposalPolicy {
+        let policy = self
+            .proposal_policy
+            .clone()
+            .unwrap_or_else(|| panic!("proposal policy isn't set"));
+        let account_id = env::predecessor_account_id();
+        require!(
+            policy
+                .roles
+                .iter()
+                .any(|role| self.acl_has_role((*role).into(), account_id.clone())),
+            "the account isn't allowed to create or approve proposals"
+        );
+        policy
+    }
+
+    /// Executes the action of the approved proposal. The time lock is checked again, because
+    /// the delay could be set after the proposal has been created. The deposit which exceeds
+    /// the deposits of the delegated actions is refunded to the proposer.
+    fn execute_proposal_action(
+        &mut self,
+        action: ProposalAction,
+        deposit: NearToken,
+        proposer: AccountId,
+    ) -> PromiseOrValue<()> {
+        let promise = match action {
+            ProposalAction::AttachFullAccessKey { public_key } => {
+                Self::attach_full_access_key_internal(public_key)
+            }
+            ProposalAction::UnrestrictedUpgrade {
+                contract_id,
+                hash,
+                state_migration_gas,
+            } => {
+                self.assert_not_time_locked(&contract_id);
+                self.upgrade_internal(
+                    contract_id,
+                    Some(hash),
+                    true,
+                    state_migration_gas,
+                    Event::UnrestrictedUpgrade,
+                )
+            }
+            ProposalAction::DelegateExecution {
+                receiver_id,
+                actions,
+            } => {
+                let total_amount = actions
+                    .iter()
+                    .fold(NearToken::from_yoctonear(0), |total, action| {
+                        total.saturating_add(action.amount)
+                    });
+                let surplus = deposit.saturating_sub(total_amount);
+                if !surplus.is_zero() {
+                    Promise::new(proposer).transfer(surplus);
+                }
+
+                let (calls, execution_id) =
+                    self.delegate_execution_internal(receiver_id, actions, deposit);
+                calls.then(Self::execution_callback(execution_id))
+            }
+            ProposalAction::SetProposalPolicy { policy } => {
+                self.set_proposal_policy_internal(policy);
+                return PromiseOrValue::Value(());
+            }
+        };
+
+        PromiseOrValue::Promise(promise)
+    }
+
+    /// Checks that the contract could be upgraded without the announcement window.
+    fn assert_not_time_locked(&self, contract_id: &AccountId) {
+        self.check_not_time_locked(contract_id)
+            .unwrap_or_else(|e| env::panic_str(&e));
+    }
+
+    fn check_not_time_locked(&self, contract_id: &AccountId) -> Result<(), String> {
+        if self.upgrade_delays.contains_key(contract_id) {
+            return Err(format!(
+                "upgrade of the contract: {contract_id} is time-locked, use `schedule_upgrade`"
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Creates a new batch upgrade of the contracts and starts upgrades which fit into the gas.
+    fn create_batch_upgrade(
+        &mut self,
+        contract_ids: Vec<AccountId>,
+        hash: String,
+        state_migration_gas: Option<u64>,
+        upgrade_gas: Option<Gas>,
+        is_downgrade: bool,
+    ) -> u64 {
+        let batch_id = self.next_batch_id;
+        self.next_batch_id += 1;
+
+        event::emit(
+            Event::BatchUpgrade,
+            &json!({
+                "batch_id": batch_id,
+                "hash": &hash,
+                "contract_ids": &contract_ids,
+                "is_downgrade": is_downgrade
+            }),
+        );
+
+        self.batch_upgrades.insert(
+            batch_id,
+            BatchUpgrade {
+                hash,
+                state_migration_gas,
+                upgrade_gas,
+                created_at: env::block_timestamp(),
+                targets: contract_ids
+                    .into_iter()
+                    .map(|contract_id| (contract_id, UpgradeStatus::Pending))
+                    .collect(),
+                is_downgrade,
+            },
+        );
+        self.dispatch_batch_upgrade(batch_id);
+
+        batch_id
+    }
+
+    /// Returns account ids of the deployed contracts matched by the filter.
+    fn select_deployments(&self, filter: Option<DeploymentFilter>) -> Vec<AccountId> {
+        let filter = filter.unwrap_or_default();
+        self.deployments
+            .iter()
+            .filter(|(_, deployment_info)| filter.matches(deployment_info))
+            .map(|(contract_id, _)| contract_id.clone())
+            .collect()
+    }
+
+    /// Resolves account ids of the contracts for each wave of the rollout.
+    /// If `is_default_hash` is set, the contracts should have the release as the latest one
+    /// according to their channels.
+    fn select_wave_contracts(
+        &self,
+        release_info: &ReleaseInfo,
+        waves: Vec<WaveSelection>,
+        is_default_hash: bool,
+    ) -> Vec<Vec<AccountId>> {
+        let is_default_release = |contract_id: &AccountId| {
+            !is_default_hash
+                || self.get_deployment_default_hash(contract_id).as_ref()
+                    == Some(&release_info.hash)
+        };
+        let explicit_ids = waves
+            .iter()
+            .filter_map(|wave| match wave {
+                WaveSelection::Contracts(contract_ids) => Some(contract_ids),
+                WaveSelection::Percentage(_) => None,
+            })
+            .flatten()
+            .collect::<Vec<_>>();
+        let unique_ids = explicit_ids.iter().collect::<BTreeSet<_>>();
+        require!(
+            unique_ids.len() == explicit_ids.len(),
+            "contract is listed in several waves"
+        );
+
+        for contract_id in &explicit_ids {
+            require!(
+                self.deployments.contains_key(*contract_id),
+                format!("contract with account id: {contract_id} hasn't been deployed")
+            );
+            require!(
+                is_default_release(contract_id),
+                format!(
+                    "contract: {contract_id} is subscribed to another release, provide the hash explicitly"
+                )
+            );
+        }
+
+        let mut remaining_ids = self
+            .deployments
+            .iter()
+            .filter(|(contract_id, info)| {
+                info.version < release_info.version
+                    && info.family == release_info.family
+                    && !unique_ids.contains(contract_id)
+                    && is_default_release(contract_id)
+            })
+            .map(|(contract_id, _)| contract_id.clone())
+            .collect::<Vec<_>>();
+        let total = remaining_ids.len().saturating_add(explicit_ids.len());
+
+        waves
+            .into_iter()
+            .enumerate()
+            .map(|(index, wave)| {
+                let contract_ids = match wave {
+                    WaveSelection::Contracts(contract_ids) => contract_ids,
+                    WaveSelection::Percentage(percentage) => {
+                        require!(
+                            (1..=100).contains(&percentage),
+                            "percentage of the wave should be between 1 and 100"
+                        );
+                        let count = total
+                            .saturating_mul(usize::from(percentage))
+                            .div_ceil(100)
+                            .min(remaining_ids.len());
+                        remaining_ids.drain(..count).collect()
+                    }
+                };
+                require!(
+                    !contract_ids.is_empty(),
+                    format!("wave {index} of the rollout doesn't contain contracts")
+                );
+                contract_ids
+            })
+            .collect()
+    }
+
+    /// Returns the hash of the latest release which is the same for all contracts according to
+    /// their channels.
+    fn resolve_batch_upgrade_hash(&self, contract_ids: &[AccountId]) -> String {
+        let hashes = contract_ids
+            .iter()
+            .map(|contract_id| self.resolve_upgrade_hash(contract_id, None))
+            .collect::<Result<BTreeSet<_>, _>>()
+            .unwrap_or_else(|e| env::panic_str(&e));
+        require!(
+            hashes.len() == 1,
+            "contracts have different latest releases, provide the hash explicitly"
+        );
+
+        hashes.into_iter().next().unwrap_or_default()
+    }
+
+    /// Checks that there are no deployments subscribed to the channel.
+    fn assert_no_channel_subscribers(&self, channel: &String) {
+        require!(
+            !self
+                .deployments
+                .values()
+                .any(|info| info.channel.as_ref() == Some(channel)),
+            format!("channel: {channel} has subscribed deployments")
+        );
+    }
+
+    /// Returns the batch upgrade started for the wave of the rollout.
+    fn get_wave_batch(&self, wave: &RolloutWave) -> &BatchUpgrade {
+        wave.batch_id
+            .and_then(|batch_id| self.batch_upgrades.get(&batch_id))
+            .unwrap_or_else(|| panic!("batch upgrade of the rollout wave doesn't exist"))
+    }
+
+    /// Starts upgrades of the pending contracts in the batch while there is enough gas for them.
+    fn dispatch_batch_upgrade(&mut self, batch_id: u64) {
+        let batch = self
+            .batch_upgrades
+            .get(&batch_id)
+            .unwrap_or_else(|| panic!("batch upgrade with id: {batch_id} doesn't exist"));
+        let pending_targets = batch.pending_targets();
+        require!(
+            !pending_targets.is_empty(),
+            "batch upgrade doesn't have pending upgrades"
+        );
+
+        let mut statuses = Vec::with_capacity(pending_targets.len());
+
+        for contract_id in pending_targets {
+            let target_gas = upgrade_method_gas(
+                &self.get_upgrade_method(&contract_id),
+                batch.state_migration_gas,
+                batch.upgrade_gas,
             )
+            .saturating_add(ADD_DEPLOYMENT_GAS)
+            .saturating_add(BATCH_UPGRADE_TARGET_GAS);
+            let available_gas = env::prepaid_gas()
+                .saturating_sub(env::used_gas())
+                .saturating_sub(BATCH_UPGRADE_RESERVED_GAS);
+
+            if available_gas < target_gas {
+                break;
+            }
+
+            // The downgrades pass the same checks as `downgrade`, so the version check is skipped
+            // for the releases with lower versions or from the downgrade chain only.
+            let hash = if batch.is_downgrade {
+                self.resolve_downgrade_hash(&contract_id, Some(batch.hash.clone()))
+            } else {
+                self.check_not_time_locked(&contract_id)
+                    .map(|()| batch.hash.clone())
+            };
+            let status = hash
+                .and_then(|hash| {
+                    self.try_upgrade(
+                        contract_id.clone(),
+                        hash,
+                        batch.is_downgrade,
+                        batch.state_migration_gas,
+                        if batch.is_downgrade {
+                            Event::Downgrade
+                        } else {
+                            Event::Upgrade
+                        },
+                        Some(batch_id),
+                        batch.upgrade_gas,
+                    )
+                })
+                .map_or_else(UpgradeStatus::Failed, |_| UpgradeStatus::InProgress);
+            statuses.push((contract_id, status));
+        }
+
+        if let Some(batch) = self.batch_upgrades.get_mut(&batch_id) {
+            batch.targets.extend(statuses);
+        }
+    }
+
+    fn dispatch_fleet_pause(&mut self, fleet_pause_id: u64) {
+        let fleet_pause = self
+            .fleet_pauses
+            .get(&fleet_pause_id)
+            .unwrap_or_else(|| panic!("fleet pause with id: {fleet_pause_id} doesn't exist"));
+        let pending_targets = fleet_pause.pending_targets();
+        require!(
+            !pending_targets.is_empty(),
+            "fleet pause doesn't have pending pauses"
+        );
+        let arguments = fleet_pause
+            .pause_arguments
+            .clone()
+            .map(String::into_bytes)
+            .unwrap_or_default();
+
+        let mut statuses = Vec::with_capacity(pending_targets.len());
+
+        for contract_id in pending_targets {
+            let methods = self.get_pause_methods(&contract_id);
+            let pause_gas = methods.pause_gas.unwrap_or(FLEET_PAUSE_GAS);
+            let callback_gas = if methods.status_method.is_some() {
+                FLEET_PAUSE_CALLBACK_GAS
+                    .saturating_mul(2)
+                    .saturating_add(FLEET_PAUSE_STATUS_GAS)
+            } else {
+                FLEET_PAUSE_CALLBACK_GAS
+            };
+            let available_gas = env::prepaid_gas()
+                .saturating_sub(env::used_gas())
+                .saturating_sub(FLEET_PAUSE_RESERVED_GAS);
+
+            if available_gas
+                < FLEET_PAUSE_TARGET_GAS
+                    .saturating_add(pause_gas)
+                    .saturating_add(callback_gas)
+            {
+                break;
+            }
+
+            let function_name = resolve_pause_method(&methods.pause, None, "pause");
+            let execution_id = self.start_execution(&contract_id, vec![function_name.clone()]);
+            Promise::new(contract_id.clone())
+                .function_call(
+                    function_name,
+                    arguments.clone(),
+                    NearToken::from_near(0),
+                    pause_gas,
+                )
+                .then(
+                    Self::ext(env::current_account_id())
+                        .with_static_gas(callback_gas)
+                        .with_unused_gas_weight(0)
+                        .finish_fleet_pause(
+                            fleet_pause_id,
+                            contract_id.clone(),
+                            execution_id,
+                            methods.status_method,
+                        ),
+                );
+            statuses.push((contract_id, execution_id));
+        }
+
+        if let Some(fleet_pause) = self.fleet_pauses.get_mut(&fleet_pause_id) {
+            for (contract_id, execution_id) in statuses {
+                fleet_pause
+                    .targets
+                    .insert(contract_id.clone(), PauseStatus::InProgress);
+                fleet_pause.execution_ids.insert(contract_id, execution_id);
+            }
+        }
+    }
+}
+
+/// Converts the pagination parameter into `usize`.
+fn to_usize(value: u32) -> usize {
+    usize::try_from(value).unwrap_or(usize::MAX)
+}
+
+/// Returns the amount of gas needed to upgrade the contract with the provided amount of gas for
+/// the state migration.
+fn upgrade_gas(state_migration_gas: Option<u64>, upgrade_gas: Option<Gas>) -> Gas {
+    upgrade_gas.unwrap_or_else(|| {
+        state_migration_gas.map_or(UPGRADE_GAS_NO_MIGRATION_GAS, |gas| {
+            UPGRADE_GAS.saturating_add(Gas::from_gas(gas))
+        })
+    })
+}
+
+/// Checks that the custom gas of the upgrade covers the gas of the state migration.
+fn assert_valid_upgrade_gas(state_migration_gas: Option<u64>, upgrade_gas: Option<Gas>) {
+    if let (Some(migration_gas), Some(upgrade_gas)) = (state_migration_gas, upgrade_gas) {
+        require!(
+            upgrade_gas.as_gas() > migration_gas,
+            "upgrade gas should be higher than the state migration gas"
+        );
+    }
+}
+
+/// Arguments of the function call made by `up_deploy_code` after deploying the code. Matches
+/// the format of `near-plugins` where the arguments are serialized as an array of bytes.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct UpFunctionCallArgs {
+    function_name: String,
+    arguments: Vec<u8>,
+    amount: NearToken,
+    gas: Gas,
+}
+
+/// Creates the promise which upgrades the contract by the upgrade method.
+/// The `gas` overrides the gas of the upgrade call computed from the state migration gas.
+fn upgrade_call(
+    contract_id: &AccountId,
+    args: UpgradeArgs,
+    method: &UpgradeMethod,
+    gas: Option<Gas>,
+) -> Promise {
+    match method {
+        UpgradeMethod::Aurora => ext_aurora::ext(contract_id.clone())
+            .with_static_gas(upgrade_gas(args.state_migration_gas, gas))
             .with_unused_gas_weight(1)
             .with_attached_deposit(NearToken::from_yoctonear(1))
-            .upgrade(args.code, args.state_migration_gas)
-            .then(
-                Self::ext(env::current_account_id())
-                    .with_static_gas(ADD_DEPLOYMENT_GAS)
-                    .with_unused_gas_weight(0)
-                    .update_deployment_info(contract_id, deployment_info),
+            .upgrade(args.code, args.state_migration_gas),
+        UpgradeMethod::NearPlugins { migration_method } => {
+            let hash = near_sdk::bs58::encode(env::sha256_array(&args.code)).into_string();
+            let function_call_args = migration_method.clone().zip(args.state_migration_gas).map(
+                |(function_name, gas)| UpFunctionCallArgs {
+                    function_name,
+                    arguments: vec![],
+                    amount: NearToken::from_yoctonear(0),
+                    gas: Gas::from_gas(gas),
+                },
+            );
+            let deploy_args =
+                json!({"hash": hash, "function_call_args": function_call_args}).to_string();
+
+            Promise::new(contract_id.clone())
+                .function_call(
+                    "up_stage_code".to_string(),
+                    near_sdk::borsh::to_vec(&args.code)
+                        .unwrap_or_else(|e| panic!("failed to serialize code: {e}")),
+                    NearToken::from_yoctonear(0),
+                    STAGE_CODE_GAS,
+                )
+                .function_call_weight(
+                    "up_deploy_code".to_string(),
+                    deploy_args.into_bytes(),
+                    NearToken::from_yoctonear(0),
+                    upgrade_gas(args.state_migration_gas, gas),
+                    GasWeight(1),
+                )
+        }
+        UpgradeMethod::Custom {
+            method_name,
+            encoding,
+            attach_one_yocto,
+        } => {
+            let gas = upgrade_gas(args.state_migration_gas, gas);
+            let arguments = match encoding {
+                UpgradeArgsEncoding::Borsh => near_sdk::borsh::to_vec(&args)
+                    .unwrap_or_else(|e| panic!("failed to serialize upgrade arguments: {e}")),
+                UpgradeArgsEncoding::Raw => args.code,
+            };
+
+            Promise::new(contract_id.clone()).function_call_weight(
+                method_name.clone(),
+                arguments,
+                NearToken::from_yoctonear(u128::from(*attach_one_yocto)),
+                gas,
+                GasWeight(1),
+            )
+        }
+    }
+}
+
+/// Returns the amount of gas attached to the promises upgrading the contract by the method.
+fn upgrade_method_gas(
+    method: &UpgradeMethod,
+    state_migration_gas: Option<u64>,
+    gas: Option<Gas>,
+) -> Gas {
+    match method {
+        UpgradeMethod::NearPlugins { .. } => {
+            STAGE_CODE_GAS.saturating_add(upgrade_gas(state_migration_gas, gas))
+        }
+        UpgradeMethod::Aurora | UpgradeMethod::Custom { .. } => {
+            upgrade_gas(state_migration_gas, gas)
+        }
+    }
+}
+
+/// Returns the split of the attached deposit between the balance of the new account and the init
+/// call. The whole deposit is transferred to the account if the split isn't provided. The balance
+/// of the account should cover the storage of the code.
+fn check_deposit_split(
+    target: DeployTarget,
+    deposit_split: Option<DepositSplit>,
+    attached_deposit: NearToken,
+    code: &[u8],
+) -> Result<DepositSplit, String> {
+    if target == DeployTarget::Existing {
+        if deposit_split.is_some() {
+            return Err("deposit split isn't supported for existing accounts".to_string());
+        }
+        return Ok(DepositSplit {
+            account_balance: NearToken::from_near(0),
+            init_deposit: NearToken::from_near(0),
+            init_gas: None,
+        });
+    }
+
+    let deposit_split = deposit_split.unwrap_or(DepositSplit {
+        account_balance: attached_deposit,
+        init_deposit: NearToken::from_near(0),
+        init_gas: None,
+    });
+    if deposit_split
+        .account_balance
+        .checked_add(deposit_split.init_deposit)
+        != Some(attached_deposit)
+    {
+        return Err(
+            "sum of the account balance and the init deposit should be equal to the attached deposit"
+                .to_string(),
+        );
+    }
+
+    let storage_cost = code_storage_cost(code);
+    if deposit_split.account_balance < storage_cost {
+        return Err(format!(
+            "account balance: {} doesn't cover the storage of the code: {}",
+            deposit_split.account_balance.as_yoctonear(),
+            storage_cost.as_yoctonear()
+        ));
+    }
+
+    Ok(deposit_split)
+}
+
+/// Returns the part of the deposit which is returned to the controller if the deployment fails:
+/// the whole deposit of the reverted batch which creates the subaccount, the deposit of the init
+/// call of the account which has been created by the account creator and nothing for the existing
+/// account. The failed creation of the account is recorded by `finish_account_creation`.
+fn refundable_deposit(target: DeployTarget, deposit_split: &DepositSplit) -> NearToken {
+    match target {
+        DeployTarget::Subaccount => deposit_split
+            .account_balance
+            .saturating_add(deposit_split.init_deposit),
+        DeployTarget::AccountCreator => deposit_split.init_deposit,
+        DeployTarget::Existing => NearToken::from_yoctonear(0),
+    }
+}
+
+/// Returns the cost of the storage of the code.
+fn code_storage_cost(code: &[u8]) -> NearToken {
+    env::storage_byte_cost().saturating_mul(u128::try_from(code.len()).unwrap_or(u128::MAX))
+}
+
+/// Returns the deposit which should be attached to the deployment: 1 yoctonear for the existing
+/// account, the sum of the deposit split if it's provided or the cost of the storage of the code.
+fn required_deploy_deposit(
+    target: DeployTarget,
+    deposit_split: Option<&DepositSplit>,
+    code: &[u8],
+) -> NearToken {
+    if target == DeployTarget::Existing {
+        return NearToken::from_yoctonear(1);
+    }
+
+    deposit_split.map_or_else(
+        || code_storage_cost(code),
+        |split| split.account_balance.saturating_add(split.init_deposit),
+    )
+}
+
+/// Returns the gas of the init call of the new contract.
+fn init_call_gas(deposit_split: &DepositSplit) -> Gas {
+    deposit_split.init_gas.unwrap_or(NEW_GAS)
+}
+
+/// Returns the gas attached to the promises created by the deployment to the target.
+fn deploy_gas(target: DeployTarget, method: &UpgradeMethod, deposit_split: &DepositSplit) -> Gas {
+    let gas = match target {
+        DeployTarget::Subaccount => init_call_gas(deposit_split),
+        DeployTarget::Existing => upgrade_method_gas(method, None, None),
+        DeployTarget::AccountCreator => CREATE_ACCOUNT_GAS
+            .saturating_add(FINISH_ACCOUNT_CREATION_GAS)
+            .saturating_add(init_call_gas(deposit_split)),
+    };
+
+    gas.saturating_add(FINISH_DEPLOY_GAS)
+}
+
+/// Creates the promise which creates the account with the code and the access keys by
+/// the `create_account_advanced` method of the account creator contract.
+fn create_account_call(
+    account_creator: AccountId,
+    new_account_id: &AccountId,
+    access_keys: Vec<AccessKeyInfo>,
+    code: &[u8],
+    deposit: NearToken,
+) -> Promise {
+    let mut full_access_keys = vec![];
+    let mut limited_access_keys = vec![];
+
+    for access_key in access_keys {
+        match access_key.permission {
+            KeyPermission::FullAccess => full_access_keys.push(access_key.public_key),
+            KeyPermission::FunctionCall {
+                allowance,
+                receiver_id,
+                method_names,
+            } => limited_access_keys.push(json!({
+                "public_key": access_key.public_key,
+                // Zero allowance means unlimited allowance for the account creator.
+                "allowance": U128(allowance.map_or(0, NearToken::as_yoctonear)),
+                "receiver_id": receiver_id.unwrap_or_else(|| new_account_id.clone()),
+                "method_names": method_names.join(","),
+            })),
+        }
+    }
+
+    let args = json!({
+        "new_account_id": new_account_id,
+        "options": {
+            "full_access_keys": full_access_keys,
+            "limited_access_keys": limited_access_keys,
+            "contract_bytes_base64": Base64VecU8(code.to_vec()),
+        }
+    });
+
+    Promise::new(account_creator).function_call(
+        "create_account_advanced".to_string(),
+        args.to_string().into_bytes(),
+        deposit,
+        CREATE_ACCOUNT_GAS,
+    )
+}
+
+/// Adds the function call action to the promise.
+fn add_function_call(promise: Promise, args: FunctionCallArgs) -> Promise {
+    promise.function_call(
+        args.function_name,
+        args.arguments.into(),
+        args.amount,
+        args.gas,
+    )
+}
+
+/// Adds the access key to the account created by the promise.
+fn add_access_key(promise: Promise, access_key: AccessKeyInfo, contract_id: &AccountId) -> Promise {
+    match access_key.permission {
+        KeyPermission::FullAccess => promise.add_full_access_key(access_key.public_key),
+        KeyPermission::FunctionCall {
+            allowance,
+            receiver_id,
+            method_names,
+        } => promise.add_access_key_allowance(
+            access_key.public_key,
+            allowance
+                .and_then(Allowance::limited)
+                .unwrap_or(Allowance::Unlimited),
+            receiver_id.unwrap_or_else(|| contract_id.clone()),
+            method_names.join(","),
+        ),
+    }
+}
+
+/// Checks that the access keys are unique and the function call access keys don't have zero
+/// allowance.
+fn assert_valid_access_keys(access_keys: &[AccessKeyInfo]) {
+    let mut public_keys = BTreeSet::new();
+
+    for access_key in access_keys {
+        require!(
+            public_keys.insert(&access_key.public_key),
+            format!(
+                "access key: {} is duplicated",
+                String::from(&access_key.public_key)
             )
+        );
+
+        if let KeyPermission::FunctionCall {
+            allowance: Some(allowance),
+            ..
+        } = &access_key.permission
+        {
+            require!(
+                !allowance.is_zero(),
+                "allowance of the access key can't be zero"
+            );
+        }
+    }
+}
+
+/// Creates the promise which executes the actions of the step of the delegated execution plan.
+fn step_promise(step: ExecutionStep) -> Promise {
+    step.actions
+        .into_iter()
+        .fold(Promise::new(step.receiver_id), add_function_call)
+}
+
+/// Returns the provided pause or unpause method if it's allowed or the first allowed method.
+fn resolve_pause_method(allowed: &[String], method_name: Option<String>, kind: &str) -> String {
+    match method_name {
+        Some(method) if allowed.contains(&method) => method,
+        Some(method) => panic!("{kind} method: {method} is not allowed"),
+        None => allowed
+            .first()
+            .cloned()
+            .unwrap_or_else(|| panic!("no {kind} methods are allowed")),
+    }
+}
+
+/// Creates the promise which calls the pause or unpause method of the contract with all
+/// remaining gas.
+fn pause_call(receiver_id: AccountId, function_name: String, arguments: Option<Value>) -> Promise {
+    let arguments = arguments.map_or_else(Vec::new, |args| {
+        near_sdk::serde_json::to_vec(&args)
+            .unwrap_or_else(|e| panic!("bad format of the pause arguments: {e}"))
+    });
+    let gas = env::prepaid_gas()
+        .saturating_sub(OUTER_DELEGATE_PAUSE_GAS)
+        .saturating_sub(DELEGATED_EXECUTION_CALLBACK_GAS);
+
+    Promise::new(receiver_id).function_call(function_name, arguments, NearToken::from_near(0), gas)
+}
+
+/// Checks that the lists of the pause and unpause methods aren't empty and contain non-empty
+/// names of the methods.
+fn assert_valid_pause_methods(methods: &PauseMethods) {
+    for (kind, names) in [("pause", &methods.pause), ("unpause", &methods.unpause)] {
+        require!(
+            !names.is_empty(),
+            format!("list of the {kind} methods can't be empty")
+        );
+        require!(
+            names.iter().all(|name| !name.is_empty()),
+            format!("{kind} method name can't be empty")
+        );
+    }
+    require!(
+        methods
+            .status_method
+            .as_ref()
+            .is_none_or(|name| !name.is_empty()),
+        "status method name can't be empty"
+    );
+    require!(
+        methods.pause_gas.is_none_or(|gas| gas.as_gas() > 0),
+        "pause gas can't be zero"
+    );
+}
+
+/// Checks that the delegation policy has non-empty and unique names of the methods.
+fn assert_valid_delegation_policy(policy: &DelegationPolicy) {
+    let mut method_names = BTreeSet::new();
+
+    for method in &policy.allowed_methods {
+        require!(
+            !method.method_name.is_empty(),
+            "allowed method name can't be empty"
+        );
+        require!(
+            method_names.insert(&method.method_name),
+            format!("method: {} is allowed more than once", method.method_name)
+        );
+    }
+}
+
+/// Checks that the upgrade method has non-empty names of the methods.
+fn assert_valid_upgrade_method(method: &UpgradeMethod) {
+    match method {
+        UpgradeMethod::Aurora => {}
+        UpgradeMethod::NearPlugins { migration_method } => {
+            require!(
+                !migration_method.as_ref().is_some_and(String::is_empty),
+                "migration method name can't be empty"
+            );
+        }
+        UpgradeMethod::Custom { method_name, .. } => {
+            require!(
+                !method_name.is_empty(),
+                "upgrade method name can't be empty"
+            );
+        }
     }
 }
 
@@ -0,0 +1,130 @@
+use near_sdk::borsh::BorshDeserialize;
+use near_sdk::collections::LazyOption;
+use near_sdk::store::IterableMap;
+use near_sdk::{env, near, AccountId};
+use std::collections::BTreeMap;
+
+use crate::types::{DeploymentInfo, ReleaseInfo, Version};
+use crate::AuroraControllerFactory;
+
+/// Storage key of the state of the contract.
+const STATE_KEY: &[u8] = b"STATE";
+
+/// Information about release in the layout of the versions 0.3.x.
+#[near(serializers = [borsh])]
+pub struct ReleaseInfoV0 {
+    pub hash: String,
+    pub version: Version,
+    pub is_blob_exist: bool,
+    pub downgrade_hash: Option<String>,
+    pub description: Option<String>,
+}
+
+impl From<ReleaseInfoV0> for ReleaseInfo {
+    fn from(info: ReleaseInfoV0) -> Self {
+        Self {
+            hash: info.hash,
+            version: info.version,
+            is_blob_exist: info.is_blob_exist,
+            downgrade_hash: info.downgrade_hash,
+            description: info.description,
+            family: None,
+        }
+    }
+}
+
+/// Deployment information of the deployed contract in the layout of the versions 0.3.x.
+#[near(serializers = [borsh])]
+pub struct DeploymentInfoV0 {
+    pub hash: String,
+    pub version: Version,
+    pub deployment_time: u64,
+    pub upgrade_times: BTreeMap<u64, Version>,
+    pub init_args: String,
+}
+
+impl From<DeploymentInfoV0> for DeploymentInfo {
+    fn from(info: DeploymentInfoV0) -> Self {
+        Self {
+            hash: info.hash,
+            version: info.version,
+            deployment_time: info.deployment_time,
+            upgrade_times: info.upgrade_times,
+            init_args: info.init_args,
+            last_error: None,
+            channel: None,
+            family: None,
+            key_policy: None,
+            access_keys: vec![],
+        }
+    }
+}
+
+/// State of the controller contract in the layout of the versions 0.3.x.
+#[near(serializers = [borsh])]
+pub struct StateV0 {
+    pub releases: IterableMap<String, ReleaseInfoV0>,
+    pub blobs: IterableMap<String, Vec<u8>>,
+    pub deployments: IterableMap<AccountId, DeploymentInfoV0>,
+    pub latest: LazyOption<ReleaseInfoV0>,
+}
+
+/// State of the controller contract stored in one of the known layouts.
+pub enum VersionedState {
+    /// State of the versions 0.3.x.
+    V0(StateV0),
+    /// State of the current version.
+    Current,
+}
+
+impl VersionedState {
+    /// Reads the state of the contract and detects its layout.
+    pub fn read() -> Self {
+        let bytes = env::storage_read(STATE_KEY)
+            .unwrap_or_else(|| env::panic_str("state of the contract doesn't exist"));
+
+        if AuroraControllerFactory::try_from_slice(&bytes).is_ok() {
+            return Self::Current;
+        }
+
+        StateV0::try_from_slice(&bytes).map_or_else(
+            |_| env::panic_str("unknown layout of the state of the contract"),
+            Self::V0,
+        )
+    }
+}
+
+impl StateV0 {
+    /// Rewrites the releases, deployments and the latest release in the current layout and moves
+    /// them with the blobs into the provided state.
+    pub fn migrate_into(self, contract: &mut AuroraControllerFactory) {
+        let Self {
+            mut releases,
+            blobs,
+            mut deployments,
+            latest,
+        } = self;
+
+        // The entries are removed and flushed before they are written in the new layout with
+        // the same prefixes, so the caches of the old collections don't overwrite them.
+        let old_releases = releases.drain().collect::<Vec<_>>();
+        releases.flush();
+        let old_deployments = deployments.drain().collect::<Vec<_>>();
+        deployments.flush();
+
+        contract.releases.extend(
+            old_releases
+                .into_iter()
+                .map(|(hash, info)| (hash, info.into())),
+        );
+        contract.deployments.extend(
+            old_deployments
+                .into_iter()
+                .map(|(contract_id, info)| (contract_id, info.into())),
+        );
+        if let Some(info) = latest.get() {
+            contract.latest.set(&info.into());
+        }
+        contract.blobs = blobs;
+    }
+}
@@ -0,0 +1,207 @@
+use near_sdk::{AccountId, Gas, NearToken};
+use std::collections::BTreeMap;
+
+use super::{dao, predecessor_account_id};
+use crate::types::{DeploymentFilter, DeploymentInfo, UpgradeStatus};
+use crate::utils::hash_256;
+use crate::AuroraControllerFactory;
+
+#[test]
+fn test_batch_upgrade() {
+    let mut contract = create_contract();
+    let silo_1: AccountId = "silo-1.near".parse().unwrap();
+    let silo_2: AccountId = "silo-2.near".parse().unwrap();
+    let silo_3: AccountId = "silo-3.near".parse().unwrap();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        prepaid_gas: Gas::from_tgas(300),
+    );
+    let batch_id = contract.batch_upgrade(
+        Some(vec![silo_1.clone(), silo_2.clone(), silo_3.clone()]),
+        None,
+        None,
+        None,
+        None,
+    );
+
+    // Only one upgrade with the default gas fits into the prepaid gas.
+    let batch = contract.get_batch_upgrade(batch_id).unwrap();
+    assert_eq!(batch.targets[&silo_1], UpgradeStatus::InProgress);
+    assert_eq!(batch.targets[&silo_2], UpgradeStatus::Pending);
+    assert_eq!(batch.targets[&silo_3], UpgradeStatus::Pending);
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        prepaid_gas: Gas::from_tgas(300),
+    );
+    contract.resume_batch_upgrade(batch_id);
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        prepaid_gas: Gas::from_tgas(300),
+    );
+    contract.resume_batch_upgrade(batch_id);
+
+    let batch = contract.get_batch_upgrade(batch_id).unwrap();
+    assert_eq!(batch.targets[&silo_2], UpgradeStatus::InProgress);
+    assert!(matches!(
+        &batch.targets[&silo_3],
+        UpgradeStatus::Failed(reason) if reason.contains("should be higher than the deployed version")
+    ));
+    assert!(!batch.is_completed());
+
+    contract.update_batch_upgrade_status(batch_id, &silo_1, true);
+    contract.update_batch_upgrade_status(batch_id, &silo_2, false);
+
+    let batch = contract.get_batch_upgrade(batch_id).unwrap();
+    assert_eq!(batch.targets[&silo_1], UpgradeStatus::Succeeded);
+    assert!(matches!(batch.targets[&silo_2], UpgradeStatus::Failed(_)));
+    assert!(batch.is_completed());
+}
+
+#[test]
+fn test_batch_upgrade_with_custom_gas() {
+    let mut contract = create_contract();
+    let silo_1: AccountId = "silo-1.near".parse().unwrap();
+    let silo_2: AccountId = "silo-2.near".parse().unwrap();
+    let silo_3: AccountId = "silo-3.near".parse().unwrap();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        prepaid_gas: Gas::from_tgas(300),
+    );
+    let batch_id = contract.batch_upgrade(
+        Some(vec![silo_1.clone(), silo_2.clone(), silo_3.clone()]),
+        None,
+        None,
+        None,
+        Some(Gas::from_tgas(40)),
+    );
+
+    // All upgrades are started in one call: (300 - 15) / (40 + 15) = 5 upgrades fit.
+    let batch = contract.get_batch_upgrade(batch_id).unwrap();
+    assert_eq!(batch.upgrade_gas, Some(Gas::from_tgas(40)));
+    assert_eq!(batch.targets[&silo_1], UpgradeStatus::InProgress);
+    assert_eq!(batch.targets[&silo_2], UpgradeStatus::InProgress);
+    assert!(matches!(
+        &batch.targets[&silo_3],
+        UpgradeStatus::Failed(reason) if reason.contains("should be higher than the deployed version")
+    ));
+    assert!(batch.pending_targets().is_empty());
+}
+
+#[test]
+#[should_panic = "upgrade gas should be higher than the state migration gas"]
+fn test_batch_upgrade_with_insufficient_gas() {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        prepaid_gas: Gas::from_tgas(300),
+    );
+    contract.batch_upgrade(
+        None,
+        None,
+        None,
+        Some(Gas::from_tgas(50).as_gas()),
+        Some(Gas::from_tgas(40)),
+    );
+}
+
+#[test]
+fn test_batch_upgrade_with_filter() {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        prepaid_gas: Gas::from_tgas(300),
+    );
+    let batch_id = contract.batch_upgrade(
+        None,
+        Some(DeploymentFilter {
+            max_version: "0.9.1".parse().ok(),
+            ..Default::default()
+        }),
+        None,
+        None,
+        None,
+    );
+
+    let batch = contract.get_batch_upgrade(batch_id).unwrap();
+    assert_eq!(
+        batch.targets,
+        BTreeMap::from([("silo-1.near".parse().unwrap(), UpgradeStatus::InProgress)])
+    );
+}
+
+#[test]
+#[should_panic = "batch upgrade doesn't have pending upgrades"]
+fn test_resume_completed_batch_upgrade() {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        prepaid_gas: Gas::from_tgas(300),
+    );
+    let batch_id = contract.batch_upgrade(
+        Some(vec!["silo-3.near".parse().unwrap()]),
+        None,
+        None,
+        None,
+        None,
+    );
+    contract.resume_batch_upgrade(batch_id);
+}
+
+pub(super) fn create_contract() -> AuroraControllerFactory {
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        input: vec![1; 256],
+        attached_deposit: NearToken::from_near(1),
+    );
+    let mut contract = AuroraControllerFactory::new(dao());
+    contract.add_release_info(
+        hash_256([1_u8; 256]),
+        "1.0.0".parse().unwrap(),
+        true,
+        None,
+        None,
+        None,
+    );
+    contract.add_release_blob();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    for (contract_id, version) in [
+        ("silo-1.near", "0.9.0"),
+        ("silo-2.near", "0.9.1"),
+        ("silo-3.near", "1.0.0"),
+    ] {
+        contract.add_deployment_info(
+            contract_id.parse().unwrap(),
+            DeploymentInfo {
+                hash: String::new(),
+                version: version.parse().unwrap(),
+                deployment_time: 0,
+                upgrade_times: BTreeMap::new(),
+                init_args: String::new(),
+                last_error: None,
+                channel: None,
+                family: None,
+                key_policy: None,
+                access_keys: vec![],
+            },
+        );
+    }
+
+    contract
+}
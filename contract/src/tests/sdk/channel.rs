@@ -0,0 +1,142 @@
+use near_sdk::{AccountId, Gas, NearToken};
+
+use super::batch::create_contract;
+use super::predecessor_account_id;
+use crate::types::WaveSelection;
+use crate::utils::hash_256;
+use crate::AuroraControllerFactory;
+
+#[test]
+fn test_upgrade_from_channel() {
+    let mut contract = create_contract_with_channel();
+    let silo: AccountId = "silo-1.near".parse().unwrap();
+
+    contract.set_deployment_channel(silo.clone(), Some("beta".to_string()));
+    assert_eq!(
+        contract.get_deployment(&silo).unwrap().channel.as_deref(),
+        Some("beta")
+    );
+
+    let scheduled_upgrade = contract.schedule_upgrade(silo, None, None, None);
+    assert_eq!(scheduled_upgrade.hash, hash_256([2_u8; 256]));
+}
+
+#[test]
+#[should_panic = "version of new release of the channel: beta should be higher than previous"]
+fn test_set_channel_release_with_lower_version() {
+    let mut contract = create_contract_with_channel();
+
+    contract.set_channel_release("beta".to_string(), hash_256([1_u8; 256]));
+}
+
+#[test]
+#[should_panic = "channel: beta has subscribed deployments"]
+fn test_remove_channel_with_subscribed_deployments() {
+    let mut contract = create_contract_with_channel();
+
+    contract.set_deployment_channel("silo-1.near".parse().unwrap(), Some("beta".to_string()));
+    contract.remove_channel("beta".to_string());
+}
+
+#[test]
+#[should_panic = "channel: beta has subscribed deployments"]
+fn test_force_remove_release_of_channel_with_subscribed_deployments() {
+    let mut contract = create_contract_with_channel();
+
+    contract.set_deployment_channel("silo-1.near".parse().unwrap(), Some("beta".to_string()));
+    contract.remove_release(&hash_256([2_u8; 256]), Some(true));
+}
+
+#[test]
+#[should_panic = "contracts have different latest releases, provide the hash explicitly"]
+fn test_batch_upgrade_of_different_channels_without_hash() {
+    let mut contract = create_contract_with_channel();
+
+    contract.set_deployment_channel("silo-1.near".parse().unwrap(), Some("beta".to_string()));
+    contract.batch_upgrade(
+        Some(vec![
+            "silo-1.near".parse().unwrap(),
+            "silo-2.near".parse().unwrap(),
+        ]),
+        None,
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_batch_upgrade_of_channel_without_hash() {
+    let mut contract = create_contract_with_channel();
+    let silo: AccountId = "silo-1.near".parse().unwrap();
+
+    contract.set_deployment_channel(silo.clone(), Some("beta".to_string()));
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        prepaid_gas: Gas::from_tgas(300),
+    );
+    let batch_id = contract.batch_upgrade(Some(vec![silo]), None, None, None, None);
+    assert_eq!(
+        contract.get_batch_upgrade(batch_id).unwrap().hash,
+        hash_256([2_u8; 256])
+    );
+}
+
+#[test]
+fn test_create_rollout_without_hash_skips_channels() {
+    let mut contract = create_contract_with_channel();
+    let silo_1: AccountId = "silo-1.near".parse().unwrap();
+    let silo_2: AccountId = "silo-2.near".parse().unwrap();
+
+    contract.set_deployment_channel(silo_1, Some("beta".to_string()));
+    let rollout_id =
+        contract.create_rollout(None, vec![WaveSelection::Percentage(100)], None, None);
+
+    let rollout = contract.get_rollout(rollout_id).unwrap();
+    assert_eq!(rollout.waves[0].contract_ids, vec![silo_2]);
+}
+
+#[test]
+#[should_panic = "contract: silo-1.near is subscribed to another release, provide the hash explicitly"]
+fn test_create_rollout_without_hash_for_channel() {
+    let mut contract = create_contract_with_channel();
+    let silo: AccountId = "silo-1.near".parse().unwrap();
+
+    contract.set_deployment_channel(silo.clone(), Some("beta".to_string()));
+    contract.create_rollout(None, vec![WaveSelection::Contracts(vec![silo])], None, None);
+}
+
+fn create_contract_with_channel() -> AuroraControllerFactory {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        input: vec![2; 256],
+        attached_deposit: NearToken::from_near(1),
+    );
+    contract.add_release_info(
+        hash_256([2_u8; 256]),
+        "1.1.0".parse().unwrap(),
+        false,
+        None,
+        None,
+        None,
+    );
+    contract.add_release_blob();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.set_channel_release("beta".to_string(), hash_256([2_u8; 256]));
+    assert_eq!(
+        contract
+            .get_channel_release(&"beta".to_string())
+            .unwrap()
+            .version,
+        "1.1.0".parse().unwrap()
+    );
+
+    contract
+}
@@ -0,0 +1,486 @@
+use near_plugins::AccessControllable;
+use near_sdk::serde_json::json;
+use near_sdk::test_utils::{get_created_receipts, MockAction};
+use near_sdk::{AccountId, Gas, GasWeight, NearToken, PromiseOrValue, PromiseResult};
+
+use super::batch::create_contract;
+use super::{function_call_args, predecessor_account_id};
+use crate::types::{
+    AllowedMethod, DelegationPolicy, ExecutionMode, ExecutionRecord, ExecutionStatus,
+    ExecutionStep, ExecutionStepResult, ExecutionStepStatus, FunctionCallArgs,
+};
+use crate::{AuroraControllerFactory, Role};
+
+#[test]
+fn test_delegate_execution_by_executor() {
+    let mut contract = create_executor_contract();
+
+    set_env!(
+        predecessor_account_id: executor(),
+        attached_deposit: NearToken::from_near(1),
+    );
+    contract.delegate_execution(
+        "silo-1.near".parse().unwrap(),
+        vec![action("set_fee", NearToken::from_near(1), 10)],
+    );
+}
+
+#[test]
+fn test_delegate_execution_by_dao_without_policy() {
+    let mut contract = create_executor_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.delegate_execution(
+        "bridge.near".parse().unwrap(),
+        vec![action("withdraw", NearToken::from_near(0), 50)],
+    );
+}
+
+#[test]
+#[should_panic = "method: withdraw isn't allowed for the receiver: silo-1.near"]
+fn test_delegate_not_allowed_method() {
+    let mut contract = create_executor_contract();
+
+    set_env!(
+        predecessor_account_id: executor(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.delegate_execution(
+        "silo-1.near".parse().unwrap(),
+        vec![
+            action("set_fee", NearToken::from_near(0), 10),
+            action("withdraw", NearToken::from_near(0), 10),
+        ],
+    );
+}
+
+#[test]
+#[should_panic = "gas: 20000000000000 of the method: set_fee exceeds the limit: 10000000000000 for the receiver: silo-1.near"]
+fn test_delegate_execution_with_exceeded_gas() {
+    let mut contract = create_executor_contract();
+
+    set_env!(
+        predecessor_account_id: executor(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.delegate_execution(
+        "silo-1.near".parse().unwrap(),
+        vec![action("set_fee", NearToken::from_near(0), 20)],
+    );
+}
+
+#[test]
+#[should_panic = "receiver: bridge.near isn't a deployed contract"]
+fn test_delegate_execution_to_unknown_receiver() {
+    let mut contract = create_executor_contract();
+
+    set_env!(
+        predecessor_account_id: executor(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.delegate_execution(
+        "bridge.near".parse().unwrap(),
+        vec![action("set_fee", NearToken::from_near(0), 10)],
+    );
+}
+
+#[test]
+#[should_panic = "method: set_fee isn't allowed for the receiver: silo-2.near"]
+fn test_delegate_execution_with_deployment_policy() {
+    let mut contract = create_executor_contract();
+    let silo_2: AccountId = "silo-2.near".parse().unwrap();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.set_deployment_delegation_policy(
+        silo_2.clone(),
+        Some(DelegationPolicy {
+            allowed_methods: vec![],
+        }),
+    );
+    assert!(contract
+        .get_delegation_policy(&silo_2)
+        .unwrap()
+        .allowed_methods
+        .is_empty());
+
+    set_env!(
+        predecessor_account_id: executor(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.delegate_execution(silo_2, vec![action("set_fee", NearToken::from_near(0), 10)]);
+}
+
+#[test]
+fn test_delegate_execution_plan() {
+    let mut contract = create_executor_contract();
+    let silo_1: AccountId = "silo-1.near".parse().unwrap();
+    let silo_2: AccountId = "silo-2.near".parse().unwrap();
+
+    set_env!(
+        predecessor_account_id: executor(),
+        attached_deposit: NearToken::from_near(3),
+    );
+    contract.delegate_execution_plan(
+        vec![
+            step("silo-1.near", NearToken::from_near(1)),
+            step("silo-2.near", NearToken::from_near(1)),
+        ],
+        ExecutionMode::Parallel,
+    );
+    assert_eq!(
+        set_fee_calls(),
+        vec![
+            (silo_1.clone(), NearToken::from_near(1)),
+            (silo_2.clone(), NearToken::from_near(1))
+        ]
+    );
+    assert_eq!(transfers(), vec![(executor(), NearToken::from_near(1))]);
+    assert_eq!(
+        function_call_args("finish_execution_plan"),
+        vec![json!({"receiver_ids": [&silo_1, &silo_2], "execution_ids": [0, 1]})]
+    );
+    assert_eq!(
+        contract.get_execution(1).unwrap().status,
+        ExecutionStatus::InProgress
+    );
+
+    set_env_with_promise_results!(
+        vec![PromiseResult::Successful(b"1".to_vec()), PromiseResult::Failed];
+        predecessor_account_id: predecessor_account_id(),
+    );
+    let results = contract.finish_execution_plan(vec![silo_1.clone(), silo_2.clone()], vec![0, 1]);
+    assert_eq!(
+        results,
+        vec![
+            ExecutionStepResult {
+                execution_id: 0,
+                receiver_id: silo_1,
+                status: ExecutionStepStatus::Succeeded,
+                output: Some("MQ==".to_string()),
+            },
+            ExecutionStepResult {
+                execution_id: 1,
+                receiver_id: silo_2,
+                status: ExecutionStepStatus::Failed,
+                output: None,
+            },
+        ]
+    );
+    assert_eq!(
+        contract.get_execution(0).unwrap().status,
+        ExecutionStatus::Succeeded {
+            output: "MQ==".to_string()
+        }
+    );
+    assert_eq!(
+        contract.get_execution(1).unwrap().status,
+        ExecutionStatus::Failed
+    );
+}
+
+#[test]
+fn test_delegate_execution_plan_with_many_steps() {
+    let mut contract = create_executor_contract();
+    let steps = (0..15)
+        .map(|_| step("silo-1.near", NearToken::from_near(0)))
+        .collect::<Vec<_>>();
+
+    set_env!(
+        predecessor_account_id: executor(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        prepaid_gas: Gas::from_tgas(300),
+    );
+    contract.delegate_execution_plan(steps, ExecutionMode::Parallel);
+    let callback_gas: Vec<_> = get_created_receipts()
+        .into_iter()
+        .flat_map(|receipt| receipt.actions)
+        .filter_map(|action| match action {
+            MockAction::FunctionCallWeight {
+                method_name,
+                prepaid_gas,
+                gas_weight,
+                ..
+            } if method_name == b"finish_execution_plan" => Some((prepaid_gas, gas_weight)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(callback_gas, vec![(Gas::from_tgas(40), GasWeight(1))]);
+
+    set_env_with_promise_results!(
+        (0..15)
+            .map(|_| PromiseResult::Successful(b"1".to_vec()))
+            .collect::<Vec<_>>();
+        predecessor_account_id: predecessor_account_id(),
+    );
+    let results =
+        contract.finish_execution_plan(vec!["silo-1.near".parse().unwrap(); 15], (0..15).collect());
+    assert_eq!(results.len(), 15);
+    assert!(results
+        .iter()
+        .all(|result| result.status == ExecutionStepStatus::Succeeded));
+    assert!(matches!(
+        contract.get_execution(14).unwrap().status,
+        ExecutionStatus::Succeeded { .. }
+    ));
+}
+
+#[test]
+fn test_delegate_sequential_execution_plan() {
+    let mut contract = create_executor_contract();
+    let silo_1: AccountId = "silo-1.near".parse().unwrap();
+    let silo_2: AccountId = "silo-2.near".parse().unwrap();
+    let steps = vec![
+        step("silo-1.near", NearToken::from_near(1)),
+        step("silo-2.near", NearToken::from_near(1)),
+    ];
+
+    set_env!(
+        predecessor_account_id: executor(),
+        attached_deposit: NearToken::from_near(2),
+    );
+    contract.delegate_execution_plan(steps.clone(), ExecutionMode::Sequential);
+    assert_eq!(
+        set_fee_calls(),
+        vec![(silo_1.clone(), NearToken::from_near(1))]
+    );
+    assert!(transfers().is_empty());
+    let args = function_call_args("finish_execution_step");
+    assert_eq!(args[0]["caller"], json!(executor()));
+    assert_eq!(args[0]["execution_ids"], json!([0, 1]));
+    assert_eq!(args[0]["receiver_id"], json!(&silo_1));
+    assert_eq!(args[0]["steps"].as_array().map(Vec::len), Some(1));
+
+    set_env_with_promise_results!(
+        vec![PromiseResult::Failed];
+        predecessor_account_id: predecessor_account_id(),
+    );
+    let PromiseOrValue::Value(results) = contract.finish_execution_step(
+        executor(),
+        vec![0, 1],
+        silo_1.clone(),
+        steps[1..].to_vec(),
+        vec![],
+    ) else {
+        panic!("execution plan should be finished after the failed step");
+    };
+    assert_eq!(
+        results
+            .iter()
+            .map(|result| (result.receiver_id.clone(), result.status))
+            .collect::<Vec<_>>(),
+        vec![
+            (silo_1, ExecutionStepStatus::Failed),
+            (silo_2, ExecutionStepStatus::Skipped)
+        ]
+    );
+    assert_eq!(transfers(), vec![(executor(), NearToken::from_near(1))]);
+    assert_eq!(
+        contract.get_execution(0).unwrap().status,
+        ExecutionStatus::Failed
+    );
+    assert_eq!(
+        contract.get_execution(1).unwrap().status,
+        ExecutionStatus::Skipped
+    );
+}
+
+#[test]
+#[should_panic = "step for the receiver: silo-2.near doesn't have actions"]
+fn test_delegate_execution_plan_with_empty_step() {
+    let mut contract = create_executor_contract();
+
+    set_env!(
+        predecessor_account_id: executor(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.delegate_execution_plan(
+        vec![
+            step("silo-1.near", NearToken::from_near(0)),
+            ExecutionStep {
+                receiver_id: "silo-2.near".parse().unwrap(),
+                actions: vec![],
+            },
+        ],
+        ExecutionMode::Parallel,
+    );
+}
+
+#[test]
+#[should_panic = "not enough deposit attached"]
+fn test_delegate_execution_plan_without_deposit() {
+    let mut contract = create_executor_contract();
+
+    set_env!(
+        predecessor_account_id: executor(),
+        attached_deposit: NearToken::from_near(1),
+    );
+    contract.delegate_execution_plan(
+        vec![
+            step("silo-1.near", NearToken::from_near(1)),
+            step("silo-2.near", NearToken::from_near(1)),
+        ],
+        ExecutionMode::Parallel,
+    );
+}
+
+#[test]
+#[should_panic = "receiver: bridge.near isn't a deployed contract"]
+fn test_delegate_execution_plan_to_unknown_receiver() {
+    let mut contract = create_executor_contract();
+
+    set_env!(
+        predecessor_account_id: executor(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.delegate_execution_plan(
+        vec![
+            step("silo-1.near", NearToken::from_near(0)),
+            step("bridge.near", NearToken::from_near(0)),
+        ],
+        ExecutionMode::Sequential,
+    );
+}
+
+#[test]
+fn test_execution_history() {
+    let mut contract = create_executor_contract();
+    let silo_1: AccountId = "silo-1.near".parse().unwrap();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.delegate_execution(
+        silo_1.clone(),
+        vec![
+            action("set_fee", NearToken::from_near(0), 10),
+            action("set_owner", NearToken::from_near(0), 10),
+        ],
+    );
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.delegate_pause(silo_1.clone(), None, None);
+
+    let executions = contract.get_executions(None, None);
+    assert_eq!(executions.len(), 2);
+    assert_eq!(
+        executions[&0],
+        ExecutionRecord {
+            receiver_id: silo_1.clone(),
+            method_names: vec!["set_fee".to_string(), "set_owner".to_string()],
+            started_at: 0,
+            status: ExecutionStatus::InProgress,
+        }
+    );
+    assert_eq!(
+        executions[&1].method_names,
+        vec!["pause_contract".to_string()]
+    );
+    assert_eq!(contract.get_executions(Some(1), None).len(), 1);
+}
+
+#[test]
+fn test_execution_history_limit() {
+    let mut contract = create_executor_contract();
+
+    for _ in 0..=100 {
+        set_env!(
+            predecessor_account_id: predecessor_account_id(),
+            attached_deposit: NearToken::from_yoctonear(1),
+        );
+        contract.delegate_pause("silo-1.near".parse().unwrap(), None, None);
+    }
+
+    let executions = contract.get_executions(None, Some(200));
+    assert_eq!(executions.len(), 100);
+    assert_eq!(executions.keys().next(), Some(&1));
+    assert!(contract.get_execution(0).is_none());
+    assert!(contract.get_execution(100).is_some());
+}
+
+fn create_executor_contract() -> AuroraControllerFactory {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.acl_grant_role(Role::Executor.into(), executor());
+    contract.set_family_delegation_policy(
+        None,
+        Some(DelegationPolicy {
+            allowed_methods: vec![AllowedMethod {
+                method_name: "set_fee".to_string(),
+                max_deposit: Some(NearToken::from_near(1)),
+                max_gas: Some(Gas::from_tgas(10)),
+            }],
+        }),
+    );
+    contract
+}
+
+fn action(function_name: &str, amount: NearToken, tgas: u64) -> FunctionCallArgs {
+    FunctionCallArgs {
+        function_name: function_name.to_string(),
+        arguments: vec![].into(),
+        amount,
+        gas: Gas::from_tgas(tgas),
+    }
+}
+
+fn executor() -> AccountId {
+    "bob.near".parse().unwrap()
+}
+
+/// Returns the receivers and the deposits of the `set_fee` calls created by the last call.
+fn set_fee_calls() -> Vec<(AccountId, NearToken)> {
+    get_created_receipts()
+        .into_iter()
+        .flat_map(|receipt| {
+            let receiver_id = receipt.receiver_id;
+            receipt
+                .actions
+                .into_iter()
+                .filter_map(move |action| match action {
+                    MockAction::FunctionCallWeight {
+                        method_name,
+                        attached_deposit,
+                        ..
+                    } if method_name == b"set_fee" => Some((receiver_id.clone(), attached_deposit)),
+                    _ => None,
+                })
+        })
+        .collect()
+}
+
+/// Returns the receivers and the amounts of the transfers created by the last call.
+fn transfers() -> Vec<(AccountId, NearToken)> {
+    get_created_receipts()
+        .into_iter()
+        .flat_map(|receipt| {
+            let receiver_id = receipt.receiver_id;
+            receipt
+                .actions
+                .into_iter()
+                .filter_map(move |action| match action {
+                    MockAction::Transfer { deposit, .. } => Some((receiver_id.clone(), deposit)),
+                    _ => None,
+                })
+        })
+        .collect()
+}
+
+fn step(receiver_id: &str, amount: NearToken) -> ExecutionStep {
+    ExecutionStep {
+        receiver_id: receiver_id.parse().unwrap(),
+        actions: vec![action("set_fee", amount, 10)],
+    }
+}
@@ -0,0 +1,60 @@
+use near_sdk::serde_json::json;
+use near_sdk::{Gas, NearToken};
+
+use super::batch::create_contract;
+use super::predecessor_account_id;
+use crate::types::{DeployOptions, DepositSplit};
+
+#[test]
+fn test_deploy_with_deposit_split() {
+    deploy_with_deposit_split(
+        NearToken::from_near(4),
+        NearToken::from_near(1),
+        Some(Gas::from_tgas(150)),
+    );
+}
+
+#[test]
+#[should_panic = "sum of the account balance and the init deposit should be equal to the attached deposit"]
+fn test_deploy_with_wrong_deposit_split() {
+    deploy_with_deposit_split(NearToken::from_near(1), NearToken::from_near(1), None);
+}
+
+#[test]
+#[should_panic = "account balance: 1 doesn't cover the storage of the code: 2560000000000000000000"]
+fn test_deploy_without_storage_deposit() {
+    deploy_with_deposit_split(
+        NearToken::from_yoctonear(1),
+        NearToken::from_near(5).saturating_sub(NearToken::from_yoctonear(1)),
+        None,
+    );
+}
+
+fn deploy_with_deposit_split(
+    account_balance: NearToken,
+    init_deposit: NearToken,
+    init_gas: Option<Gas>,
+) {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_near(5),
+    );
+    contract.deploy(
+        "silo-4.near".parse().unwrap(),
+        "new".to_string(),
+        json!({}),
+        None,
+        None,
+        None,
+        Some(DeployOptions {
+            deposit_split: Some(DepositSplit {
+                account_balance,
+                init_deposit,
+                init_gas,
+            }),
+            ..Default::default()
+        }),
+    );
+}
@@ -0,0 +1,104 @@
+use near_sdk::{AccountId, NearToken};
+use std::collections::BTreeMap;
+
+use super::{dao, predecessor_account_id};
+use crate::types::DeploymentInfo;
+use crate::utils::hash_256;
+use crate::AuroraControllerFactory;
+
+#[test]
+fn test_get_downgrade_chain() {
+    let contract = create_contract();
+
+    let chain = contract
+        .get_downgrade_chain(&hash_256([3_u8; 256]))
+        .into_iter()
+        .map(|release_info| release_info.hash)
+        .collect::<Vec<_>>();
+    assert_eq!(chain, vec![hash_256([2_u8; 256]), hash_256([1_u8; 256])]);
+    assert!(contract
+        .get_downgrade_chain(&hash_256([1_u8; 256]))
+        .is_empty());
+}
+
+#[test]
+fn test_downgrade_to_release_in_chain() {
+    let mut contract = create_contract();
+
+    contract.downgrade(silo(), Some(hash_256([1_u8; 256])), Some(1_000_000));
+}
+
+#[test]
+fn test_downgrade_time_locked_contract() {
+    let mut contract = create_contract();
+
+    contract.set_upgrade_delay(silo(), Some(3_600_000_000_000));
+    contract.downgrade(silo(), Some(hash_256([2_u8; 256])), None);
+}
+
+#[test]
+#[should_panic = "downgrade version: 1.3.0 should be lower than the deployed version: 1.2.0"]
+fn test_downgrade_to_higher_version() {
+    let mut contract = create_contract();
+
+    add_release(&mut contract, 4, "1.3.0", None);
+    contract.downgrade(silo(), Some(hash_256([4_u8; 256])), None);
+}
+
+fn create_contract() -> AuroraControllerFactory {
+    set_env!(predecessor_account_id: predecessor_account_id());
+    let mut contract = AuroraControllerFactory::new(dao());
+
+    add_release(&mut contract, 1, "1.0.0", None);
+    add_release(&mut contract, 2, "1.1.0", Some(hash_256([1_u8; 256])));
+    add_release(&mut contract, 3, "1.2.0", Some(hash_256([2_u8; 256])));
+
+    contract.add_deployment_info(
+        silo(),
+        DeploymentInfo {
+            hash: hash_256([3_u8; 256]),
+            version: "1.2.0".parse().unwrap(),
+            deployment_time: 0,
+            upgrade_times: BTreeMap::new(),
+            init_args: String::new(),
+            last_error: None,
+            channel: None,
+            family: None,
+            key_policy: None,
+            access_keys: vec![],
+        },
+    );
+
+    contract
+}
+
+fn add_release(
+    contract: &mut AuroraControllerFactory,
+    byte: u8,
+    version: &str,
+    downgrade_hash: Option<String>,
+) {
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        input: vec![byte; 256],
+        attached_deposit: NearToken::from_near(1),
+    );
+    contract.add_release_info(
+        hash_256([byte; 256]),
+        version.parse().unwrap(),
+        false,
+        downgrade_hash,
+        None,
+        None,
+    );
+    contract.add_release_blob();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+}
+
+fn silo() -> AccountId {
+    "silo.near".parse().unwrap()
+}
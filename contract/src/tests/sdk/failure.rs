@@ -0,0 +1,303 @@
+use near_sdk::serde_json::json;
+use near_sdk::{AccountId, NearToken, PromiseResult};
+use std::collections::BTreeMap;
+
+use super::{dao, function_call_args, predecessor_account_id};
+use crate::types::{DeployTarget, DeploymentAction, DeploymentInfo, FailedDeployment};
+use crate::utils::hash_256;
+use crate::AuroraControllerFactory;
+
+#[test]
+fn test_record_failed_upgrade() {
+    let mut contract = create_contract();
+    let silo: AccountId = "silo.near".parse().unwrap();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.add_deployment_info(silo.clone(), deployment_info("", "0.9.0"));
+    contract.record_deployment_failure(
+        silo.clone(),
+        deployment_info(&hash_256([1_u8; 256]), "1.0.0"),
+        DeploymentAction::Upgrade,
+    );
+
+    let deployment = contract.get_deployment(&silo).unwrap();
+    let last_error = deployment.last_error.unwrap();
+    assert_eq!(deployment.version, "0.9.0".parse().unwrap());
+    assert_eq!(last_error.action, DeploymentAction::Upgrade);
+    assert_eq!(last_error.hash, hash_256([1_u8; 256]));
+    assert_eq!(last_error.version, "1.0.0".parse().unwrap());
+}
+
+#[test]
+fn test_record_failed_deployment() {
+    let mut contract = create_contract();
+    let silo: AccountId = "silo.near".parse().unwrap();
+
+    set_env_with_promise_results!(
+        vec![PromiseResult::Failed];
+        predecessor_account_id: predecessor_account_id(),
+    );
+    contract.finish_deploy(
+        silo.clone(),
+        deployment_info(&hash_256([1_u8; 256]), "1.0.0"),
+        DeployTarget::Subaccount,
+        predecessor_account_id(),
+        NearToken::from_near(5),
+        NearToken::from_near(5),
+    );
+
+    assert!(contract.get_deployment(&silo).is_none());
+    let failed_deployment = contract.get_failed_deployment(&silo).unwrap();
+    assert_eq!(failed_deployment.hash, hash_256([1_u8; 256]));
+    assert_eq!(failed_deployment.version, "1.0.0".parse().unwrap());
+    assert_eq!(
+        failed_deployment.refundable_deposit,
+        NearToken::from_near(5)
+    );
+    assert_eq!(failed_deployment.refunded_to, None);
+    assert_eq!(
+        function_call_args("refund_returned_deposit"),
+        vec![json!({"contract_id": silo})]
+    );
+}
+
+#[test]
+fn test_deploy_after_failed_deployment() {
+    let mut contract = create_contract();
+    let silo: AccountId = "silo.near".parse().unwrap();
+    contract.failed_deployments.insert(
+        silo.clone(),
+        FailedDeployment {
+            refunded_to: Some(predecessor_account_id()),
+            ..failed_deployment()
+        },
+    );
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_near(5),
+    );
+    contract.deploy(silo, "new".to_string(), json!({}), None, None, None, None);
+}
+
+#[test]
+#[should_panic = "deposit of the failed deployment of silo.near isn't refunded yet"]
+fn test_deploy_before_refund_of_failed_deployment() {
+    let mut contract = create_contract();
+    let silo: AccountId = "silo.near".parse().unwrap();
+    contract
+        .failed_deployments
+        .insert(silo.clone(), failed_deployment());
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_near(5),
+    );
+    contract.deploy(silo, "new".to_string(), json!({}), None, None, None, None);
+}
+
+#[test]
+#[should_panic = "contract with account id: silo.near hasn't been deployed"]
+fn test_upgrade_after_failed_deployment() {
+    let mut contract = create_contract();
+    let silo: AccountId = "silo.near".parse().unwrap();
+    contract
+        .failed_deployments
+        .insert(silo.clone(), failed_deployment());
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.upgrade(silo, None, None);
+}
+
+#[test]
+fn test_refund_failed_deployment() {
+    let mut contract = create_contract();
+    let silo: AccountId = "silo.near".parse().unwrap();
+    contract
+        .failed_deployments
+        .insert(silo.clone(), failed_deployment());
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        account_balance: NearToken::from_near(100),
+    );
+    let receiver_id = contract.refund_failed_deployment(silo.clone());
+    assert_eq!(receiver_id, predecessor_account_id());
+    assert_eq!(
+        contract.get_failed_deployment(&silo).unwrap().refunded_to,
+        Some(predecessor_account_id())
+    );
+}
+
+#[test]
+fn test_refund_failed_deployment_to_beneficiary() {
+    let mut contract = create_contract();
+    let silo: AccountId = "silo.near".parse().unwrap();
+    let beneficiary: AccountId = "treasury.near".parse().unwrap();
+    contract
+        .failed_deployments
+        .insert(silo.clone(), failed_deployment());
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        account_balance: NearToken::from_near(100),
+    );
+    contract.set_refund_beneficiary(Some(beneficiary.clone()));
+    assert_eq!(contract.get_refund_beneficiary(), Some(beneficiary.clone()));
+    assert_eq!(contract.refund_failed_deployment(silo), beneficiary);
+}
+
+#[test]
+#[should_panic = "deposit of silo.near is already refunded to alice.near"]
+fn test_refund_failed_deployment_twice() {
+    let mut contract = create_contract();
+    let silo: AccountId = "silo.near".parse().unwrap();
+    contract
+        .failed_deployments
+        .insert(silo.clone(), failed_deployment());
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        account_balance: NearToken::from_near(100),
+    );
+    contract.refund_failed_deployment(silo.clone());
+    contract.refund_failed_deployment(silo);
+}
+
+#[test]
+#[should_panic = "deposit of silo.near isn't returned to the controller"]
+fn test_refund_failed_deployment_to_existing_account() {
+    let mut contract = create_contract();
+    let silo: AccountId = "silo.near".parse().unwrap();
+    contract.failed_deployments.insert(
+        silo.clone(),
+        FailedDeployment {
+            target: DeployTarget::Existing,
+            deposit: NearToken::from_yoctonear(1),
+            refundable_deposit: NearToken::from_yoctonear(0),
+            ..failed_deployment()
+        },
+    );
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        account_balance: NearToken::from_near(100),
+    );
+    contract.refund_failed_deployment(silo);
+}
+
+#[test]
+#[should_panic = "doesn't cover the returned deposits: 8000000000000000000000000"]
+fn test_refund_failed_deployment_without_returned_deposits() {
+    let mut contract = create_contract();
+    let silo: AccountId = "silo.near".parse().unwrap();
+    contract
+        .failed_deployments
+        .insert(silo.clone(), failed_deployment());
+    contract.failed_deployments.insert(
+        "silo-2.near".parse().unwrap(),
+        FailedDeployment {
+            target: DeployTarget::AccountCreator,
+            refundable_deposit: NearToken::from_near(3),
+            ..failed_deployment()
+        },
+    );
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        account_balance: NearToken::from_near(7),
+    );
+    contract.refund_failed_deployment(silo);
+}
+
+#[test]
+#[should_panic = "doesn't cover the returned deposits: 5000000000000000000000000"]
+fn test_refund_failed_deployment_without_balance() {
+    let mut contract = create_contract();
+    let silo: AccountId = "silo.near".parse().unwrap();
+    contract
+        .failed_deployments
+        .insert(silo.clone(), failed_deployment());
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        account_balance: NearToken::from_near(1),
+    );
+    contract.refund_failed_deployment(silo);
+}
+
+#[test]
+fn test_remove_failed_deployment() {
+    let mut contract = create_contract();
+    let silo: AccountId = "silo.near".parse().unwrap();
+    contract
+        .failed_deployments
+        .insert(silo.clone(), failed_deployment());
+    assert_eq!(contract.get_failed_deployments().len(), 1);
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.remove_failed_deployment(silo.clone());
+    assert!(contract.get_failed_deployment(&silo).is_none());
+}
+
+fn create_contract() -> AuroraControllerFactory {
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        input: vec![1; 256],
+        attached_deposit: NearToken::from_near(1),
+    );
+    let mut contract = AuroraControllerFactory::new(dao());
+    contract.add_release_info(
+        hash_256([1_u8; 256]),
+        "1.0.0".parse().unwrap(),
+        true,
+        None,
+        None,
+        None,
+    );
+    contract.add_release_blob();
+    contract
+}
+
+fn deployment_info(hash: &str, version: &str) -> DeploymentInfo {
+    DeploymentInfo {
+        hash: hash.to_string(),
+        version: version.parse().unwrap(),
+        deployment_time: 0,
+        upgrade_times: BTreeMap::new(),
+        init_args: String::new(),
+        last_error: None,
+        channel: None,
+        family: None,
+        key_policy: None,
+        access_keys: vec![],
+    }
+}
+
+fn failed_deployment() -> FailedDeployment {
+    FailedDeployment {
+        target: DeployTarget::Subaccount,
+        payer: predecessor_account_id(),
+        hash: hash_256([1_u8; 256]),
+        version: "1.0.0".parse().unwrap(),
+        deposit: NearToken::from_near(5),
+        refundable_deposit: NearToken::from_near(5),
+        refunded_to: None,
+        timestamp: 0,
+    }
+}
@@ -0,0 +1,130 @@
+use near_sdk::serde_json::json;
+use near_sdk::NearToken;
+
+use super::batch::create_contract;
+use super::predecessor_account_id;
+use crate::types::Preflight;
+use crate::utils::hash_256;
+use crate::AuroraControllerFactory;
+
+#[test]
+#[should_panic = "release family: bridge doesn't match the deployment family: default"]
+fn test_upgrade_to_release_of_other_family() {
+    let mut contract = create_contract_with_bridge_release();
+
+    contract.upgrade(
+        "silo-1.near".parse().unwrap(),
+        Some(hash_256([2_u8; 256])),
+        None,
+    );
+}
+
+#[test]
+#[should_panic = "downgrade release family: default doesn't match the release family: bridge"]
+fn test_add_release_with_downgrade_of_other_family() {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_near(1),
+    );
+    contract.add_release_info(
+        hash_256([2_u8; 256]),
+        "2.0.0".parse().unwrap(),
+        false,
+        Some(hash_256([1_u8; 256])),
+        None,
+        Some("bridge".to_string()),
+    );
+}
+
+#[test]
+#[should_panic = "release family: bridge doesn't match the requested family: engine"]
+fn test_deploy_release_of_other_family() {
+    let mut contract = create_contract_with_bridge_release();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_near(5),
+    );
+    contract.deploy(
+        "bridge.near".parse().unwrap(),
+        "new".to_string(),
+        json!({}),
+        Some(hash_256([2_u8; 256])),
+        None,
+        Some("engine".to_string()),
+        None,
+    );
+}
+
+#[test]
+fn test_set_latest_release_of_family() {
+    let mut contract = create_contract_with_bridge_release();
+    let bridge_hash = hash_256([2_u8; 256]);
+
+    contract.set_latest_release(&bridge_hash);
+    assert_eq!(contract.get_latest_release_hash(), hash_256([1_u8; 256]));
+    assert_eq!(
+        contract
+            .get_family_latest_release(Some("bridge".to_string()))
+            .unwrap()
+            .hash,
+        bridge_hash
+    );
+    assert!(contract.get_release_references(&bridge_hash).is_latest);
+    assert!(
+        contract
+            .get_release_references(&hash_256([1_u8; 256]))
+            .is_latest
+    );
+
+    let Preflight::Ready(plan) = contract.preflight_deploy(
+        "bridge.near".parse().unwrap(),
+        None,
+        None,
+        Some("bridge".to_string()),
+        None,
+    ) else {
+        panic!("deployment of the latest release of the family should be ready");
+    };
+    assert_eq!(plan.release_info.hash, bridge_hash);
+}
+
+#[test]
+fn test_remove_latest_release_of_family() {
+    let mut contract = create_contract_with_bridge_release();
+    let bridge_hash = hash_256([2_u8; 256]);
+
+    contract.set_latest_release(&bridge_hash);
+    contract.remove_release(&bridge_hash, Some(true));
+    assert!(contract
+        .get_family_latest_release(Some("bridge".to_string()))
+        .is_none());
+    assert_eq!(contract.get_latest_release_hash(), hash_256([1_u8; 256]));
+}
+
+fn create_contract_with_bridge_release() -> AuroraControllerFactory {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        input: vec![2; 256],
+        attached_deposit: NearToken::from_near(1),
+    );
+    contract.add_release_info(
+        hash_256([2_u8; 256]),
+        "2.0.0".parse().unwrap(),
+        false,
+        None,
+        None,
+        Some("bridge".to_string()),
+    );
+    contract.add_release_blob();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract
+}
@@ -0,0 +1,221 @@
+use near_sdk::test_utils::MockAction;
+use near_sdk::{AccountId, Gas, NearToken, PromiseResult};
+
+use super::batch::create_contract;
+use super::{created_actions, function_call_args, predecessor_account_id};
+use crate::types::{DeploymentFilter, ExecutionStatus, PauseMethods, PauseStatus};
+
+#[test]
+fn test_pause_all() {
+    let mut contract = create_contract();
+    let silo_1: AccountId = "silo-1.near".parse().unwrap();
+    let silo_2: AccountId = "silo-2.near".parse().unwrap();
+    let silo_3: AccountId = "silo-3.near".parse().unwrap();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        prepaid_gas: Gas::from_tgas(300),
+    );
+    let fleet_pause_id = contract.pause_all(
+        None,
+        Some(DeploymentFilter {
+            max_version: "1.0.0".parse().ok(),
+            ..Default::default()
+        }),
+        None,
+    );
+
+    let fleet_pause = contract.get_fleet_pause(fleet_pause_id).unwrap();
+    assert_eq!(fleet_pause.targets.len(), 2);
+    assert_eq!(fleet_pause.targets[&silo_1], PauseStatus::InProgress);
+    assert_eq!(fleet_pause.targets[&silo_2], PauseStatus::InProgress);
+    let execution_ids = fleet_pause.execution_ids;
+    assert_eq!(
+        contract
+            .get_execution(execution_ids[&silo_1])
+            .unwrap()
+            .method_names,
+        vec!["pause_contract".to_string()]
+    );
+
+    set_env_with_promise_results!(
+        vec![PromiseResult::Successful(vec![])];
+        predecessor_account_id: predecessor_account_id(),
+    );
+    contract.finish_fleet_pause(fleet_pause_id, silo_1.clone(), execution_ids[&silo_1], None);
+    set_env_with_promise_results!(
+        vec![PromiseResult::Failed];
+        predecessor_account_id: predecessor_account_id(),
+    );
+    contract.finish_fleet_pause(fleet_pause_id, silo_2.clone(), execution_ids[&silo_2], None);
+    assert_eq!(
+        contract
+            .get_execution(execution_ids[&silo_2])
+            .unwrap()
+            .status,
+        ExecutionStatus::Failed
+    );
+    assert!(contract
+        .get_fleet_pause(fleet_pause_id)
+        .unwrap()
+        .is_completed());
+
+    let status = contract.get_fleet_pause_status(None, None);
+    assert_eq!(status[&silo_1], Some(PauseStatus::Unverified));
+    assert!(matches!(status[&silo_2], Some(PauseStatus::Failed(_))));
+    assert_eq!(status[&silo_3], None);
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.delegate_unpause(silo_1.clone(), None, None);
+    let args = function_call_args("finish_delegated_unpause");
+    assert_eq!(args[0]["fleet_pause_id"], fleet_pause_id);
+
+    set_env_with_promise_results!(
+        vec![PromiseResult::Successful(vec![])];
+        predecessor_account_id: predecessor_account_id(),
+    );
+    contract.finish_delegated_unpause(
+        args[0]["execution_id"].as_u64().unwrap(),
+        silo_1.clone(),
+        fleet_pause_id,
+    );
+    assert_eq!(
+        contract.get_fleet_pause_status(None, None)[&silo_1],
+        Some(PauseStatus::Unpaused)
+    );
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.remove_fleet_pause(fleet_pause_id);
+    assert!(contract.get_fleet_pause(fleet_pause_id).is_none());
+    assert_eq!(contract.get_fleet_pause_status(None, None)[&silo_2], None);
+}
+
+#[test]
+#[should_panic = "fleet pause hasn't been completed yet"]
+fn test_remove_fleet_pause_in_progress() {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        prepaid_gas: Gas::from_tgas(300),
+    );
+    let fleet_pause_id = contract.pause_all(Some(vec!["silo-3.near".parse().unwrap()]), None, None);
+    contract.remove_fleet_pause(fleet_pause_id);
+}
+
+#[test]
+#[should_panic = "no contracts to pause"]
+fn test_pause_all_without_matched_contracts() {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        prepaid_gas: Gas::from_tgas(300),
+    );
+    contract.pause_all(
+        None,
+        Some(DeploymentFilter {
+            family: Some("bridge".to_string()),
+            ..Default::default()
+        }),
+        None,
+    );
+}
+
+#[test]
+fn test_pause_all_with_status_method() {
+    let mut contract = create_contract();
+    let silo_1: AccountId = "silo-1.near".parse().unwrap();
+    let silo_2: AccountId = "silo-2.near".parse().unwrap();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.set_family_pause_methods(
+        None,
+        Some(PauseMethods {
+            pause: vec!["pa_pause_feature".to_string()],
+            unpause: vec!["pa_unpause_feature".to_string()],
+            pause_gas: Some(Gas::from_tgas(30)),
+            status_method: Some("pa_is_paused".to_string()),
+        }),
+    );
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        prepaid_gas: Gas::from_tgas(300),
+    );
+    let fleet_pause_id = contract.pause_all(
+        Some(vec![silo_1.clone(), silo_2.clone()]),
+        None,
+        Some(near_sdk::serde_json::json!({"key": "ALL"})),
+    );
+    let pause_gas: Vec<_> = created_actions()
+        .into_iter()
+        .filter_map(|action| match action {
+            MockAction::FunctionCallWeight {
+                method_name,
+                prepaid_gas,
+                ..
+            } if method_name == b"pa_pause_feature" => Some(prepaid_gas),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(pause_gas, vec![Gas::from_tgas(30); 2]);
+    assert_eq!(
+        function_call_args("finish_fleet_pause")[0]["status_method"],
+        "pa_is_paused"
+    );
+
+    set_env_with_promise_results!(
+        vec![PromiseResult::Successful(vec![])];
+        predecessor_account_id: predecessor_account_id(),
+    );
+    let execution_id = contract
+        .get_fleet_pause(fleet_pause_id)
+        .unwrap()
+        .execution_ids[&silo_1];
+    contract.finish_fleet_pause(
+        fleet_pause_id,
+        silo_1.clone(),
+        execution_id,
+        Some("pa_is_paused".to_string()),
+    );
+    assert_eq!(
+        function_call_args("pa_is_paused"),
+        vec![near_sdk::serde_json::json!({"key": "ALL"})]
+    );
+    assert_eq!(
+        contract.get_fleet_pause(fleet_pause_id).unwrap().targets[&silo_1],
+        PauseStatus::InProgress
+    );
+
+    set_env_with_promise_results!(
+        vec![PromiseResult::Successful(b"true".to_vec())];
+        predecessor_account_id: predecessor_account_id(),
+    );
+    contract.confirm_fleet_pause(fleet_pause_id, silo_1.clone());
+    set_env_with_promise_results!(
+        vec![PromiseResult::Successful(b"false".to_vec())];
+        predecessor_account_id: predecessor_account_id(),
+    );
+    contract.confirm_fleet_pause(fleet_pause_id, silo_2.clone());
+
+    let fleet_pause = contract.get_fleet_pause(fleet_pause_id).unwrap();
+    assert_eq!(fleet_pause.targets[&silo_1], PauseStatus::Paused);
+    assert_eq!(
+        fleet_pause.targets[&silo_2],
+        PauseStatus::Failed("contract isn't paused".to_string())
+    );
+}
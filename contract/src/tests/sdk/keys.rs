@@ -0,0 +1,187 @@
+use near_sdk::serde_json::json;
+use near_sdk::test_utils::MockAction;
+use near_sdk::NearToken;
+
+use super::batch::create_contract;
+use super::{created_actions, function_call_args, predecessor_account_id};
+use crate::types::{AccessKeyInfo, DeployOptions, KeyPermission, KeyPolicy};
+
+#[test]
+fn test_set_default_access_keys() {
+    let mut contract = create_contract();
+    let access_keys = vec![
+        AccessKeyInfo {
+            public_key: "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp"
+                .parse()
+                .unwrap(),
+            permission: KeyPermission::FullAccess,
+        },
+        AccessKeyInfo {
+            public_key: "ed25519:9DxPUv4GeY2pUzGbQbpX4XKmW6GxqUvfBKCfR7wHqeR3"
+                .parse()
+                .unwrap(),
+            permission: KeyPermission::FunctionCall {
+                allowance: Some(NearToken::from_near(1)),
+                receiver_id: None,
+                method_names: vec!["pause_contract".to_string()],
+            },
+        },
+    ];
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.set_default_access_keys(access_keys.clone());
+    assert_eq!(contract.get_default_access_keys(), access_keys);
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_near(5),
+    );
+    contract.deploy(
+        "silo-4.near".parse().unwrap(),
+        "new".to_string(),
+        json!({}),
+        None,
+        None,
+        None,
+        None,
+    );
+
+    // The default keys of the controller are added instead of the key of the signer.
+    let added_keys = created_actions()
+        .into_iter()
+        .filter(|action| {
+            matches!(
+                action,
+                MockAction::AddKeyWithFullAccess { .. } | MockAction::AddKeyWithFunctionCall { .. }
+            )
+        })
+        .count();
+    assert_eq!(added_keys, 2);
+
+    let args = function_call_args("finish_deploy");
+    assert_eq!(
+        args[0]["deployment_info"]["key_policy"],
+        json!("controller_default")
+    );
+    assert_eq!(
+        args[0]["deployment_info"]["access_keys"],
+        json!(access_keys)
+    );
+}
+
+#[test]
+fn test_deploy_without_default_access_keys() {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_near(5),
+    );
+    contract.deploy(
+        "silo-4.near".parse().unwrap(),
+        "new".to_string(),
+        json!({}),
+        None,
+        None,
+        None,
+        None,
+    );
+
+    // The key of the signer is added as before while the default keys aren't set.
+    let full_access_keys = created_actions()
+        .into_iter()
+        .filter(|action| matches!(action, MockAction::AddKeyWithFullAccess { .. }))
+        .count();
+    assert_eq!(full_access_keys, 1);
+    assert_eq!(
+        function_call_args("finish_deploy")[0]["deployment_info"]["key_policy"],
+        json!("signer")
+    );
+}
+
+#[test]
+#[should_panic = "default access keys of the controller aren't set"]
+fn test_deploy_with_unset_default_access_keys() {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_near(5),
+    );
+    contract.deploy(
+        "silo-4.near".parse().unwrap(),
+        "new".to_string(),
+        json!({}),
+        None,
+        None,
+        None,
+        Some(DeployOptions {
+            key_policy: Some(KeyPolicy::ControllerDefault),
+            ..Default::default()
+        }),
+    );
+}
+
+#[test]
+#[should_panic = "access key: ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp is duplicated"]
+fn test_deploy_with_duplicated_keys() {
+    let mut contract = create_contract();
+    let access_key = AccessKeyInfo {
+        public_key: "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp"
+            .parse()
+            .unwrap(),
+        permission: KeyPermission::FullAccess,
+    };
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_near(5),
+    );
+    contract.deploy(
+        "silo-4.near".parse().unwrap(),
+        "new".to_string(),
+        json!({}),
+        None,
+        None,
+        None,
+        Some(DeployOptions {
+            key_policy: Some(KeyPolicy::Keys(vec![access_key.clone(), access_key])),
+            ..Default::default()
+        }),
+    );
+}
+
+#[test]
+#[should_panic = "allowance of the access key can't be zero"]
+fn test_deploy_with_zero_allowance() {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_near(5),
+    );
+    contract.deploy(
+        "silo-4.near".parse().unwrap(),
+        "new".to_string(),
+        json!({}),
+        None,
+        None,
+        None,
+        Some(DeployOptions {
+            key_policy: Some(KeyPolicy::Keys(vec![AccessKeyInfo {
+                public_key: "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp"
+                    .parse()
+                    .unwrap(),
+                permission: KeyPermission::FunctionCall {
+                    allowance: Some(NearToken::from_near(0)),
+                    receiver_id: None,
+                    method_names: vec![],
+                },
+            }])),
+            ..Default::default()
+        }),
+    );
+}
@@ -19,3 +19,18 @@ macro_rules! set_env {
         near_sdk::testing_env!(builder.build());
     };
 }
+
+macro_rules! set_env_with_promise_results {
+    ($results:expr; $($key:ident:$value:expr),* $(,)?) => {
+        let mut builder = near_sdk::test_utils::VMContextBuilder::new();
+        let mut builder = &mut builder;
+        builder = inner_set_env!(builder, $($key: $value),*);
+        near_sdk::testing_env!(
+            builder.build(),
+            near_sdk::test_vm_config(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            $results
+        );
+    };
+}
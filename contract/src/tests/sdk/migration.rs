@@ -0,0 +1,113 @@
+use near_sdk::collections::LazyOption;
+use near_sdk::store::IterableMap;
+use near_sdk::{env, AccountId};
+use std::collections::BTreeMap;
+
+use super::{dao, predecessor_account_id};
+use crate::keys::Prefix;
+use crate::migration::{DeploymentInfoV0, ReleaseInfoV0, StateV0};
+use crate::types::{DeploymentInfo, ReleaseInfo};
+use crate::AuroraControllerFactory;
+
+const HASH: &str = "2661920f2409dd6c8adeb0c44972959f232b6429afa913845d0fd95e7e768234";
+
+#[test]
+fn test_migrate_state_v0() {
+    set_env!(predecessor_account_id: predecessor_account_id());
+    write_state_v0();
+
+    let contract = AuroraControllerFactory::migrate();
+    let silo: AccountId = "silo.near".parse().unwrap();
+
+    assert_eq!(contract.get_releases(), vec![release_info()]);
+    assert_eq!(contract.get_latest_release_hash(), HASH);
+    assert_eq!(
+        contract.get_release_blob(&release_info()).map(Vec::len),
+        Ok(256)
+    );
+    assert_eq!(
+        contract.get_deployment(&silo),
+        Some(DeploymentInfo {
+            hash: HASH.to_string(),
+            version: "1.0.0".parse().unwrap(),
+            deployment_time: 1,
+            upgrade_times: BTreeMap::from([(2, "1.0.0".parse().unwrap())]),
+            init_args: "{}".to_string(),
+            last_error: None,
+            channel: None,
+            family: None,
+            key_policy: None,
+            access_keys: vec![],
+        })
+    );
+    assert!(contract.get_family_latest_release(None).is_some());
+}
+
+#[test]
+#[should_panic = "only DAO could migrate the state of the contract"]
+fn test_migrate_state_v0_by_not_dao() {
+    set_env!(predecessor_account_id: predecessor_account_id());
+    write_state_v0();
+
+    set_env!(predecessor_account_id: "bob.near".parse::<AccountId>().unwrap());
+    let _ = AuroraControllerFactory::migrate();
+}
+
+#[test]
+#[should_panic = "state of the contract is already migrated"]
+fn test_migrate_current_state() {
+    set_env!(predecessor_account_id: predecessor_account_id());
+    let contract = AuroraControllerFactory::new(dao());
+    env::state_write(&contract);
+    drop(contract);
+
+    let _ = AuroraControllerFactory::migrate();
+}
+
+/// Initializes the roles and writes the state of the controller of the versions 0.3.x with
+/// one release and one deployment.
+fn write_state_v0() {
+    drop(AuroraControllerFactory::new(dao()));
+
+    let mut state = StateV0 {
+        releases: IterableMap::new(Prefix::Releases),
+        blobs: IterableMap::new(Prefix::Blobs),
+        deployments: IterableMap::new(Prefix::Deployments),
+        latest: LazyOption::new(Prefix::LatestRelease, None),
+    };
+    state.releases.insert(HASH.to_string(), release_info_v0());
+    state.blobs.insert(HASH.to_string(), vec![1; 256]);
+    state.deployments.insert(
+        "silo.near".parse().unwrap(),
+        DeploymentInfoV0 {
+            hash: HASH.to_string(),
+            version: "1.0.0".parse().unwrap(),
+            deployment_time: 1,
+            upgrade_times: BTreeMap::from([(2, "1.0.0".parse().unwrap())]),
+            init_args: "{}".to_string(),
+        },
+    );
+    state.latest.set(&release_info_v0());
+    env::state_write(&state);
+}
+
+fn release_info_v0() -> ReleaseInfoV0 {
+    ReleaseInfoV0 {
+        hash: HASH.to_string(),
+        version: "1.0.0".parse().unwrap(),
+        is_blob_exist: true,
+        downgrade_hash: None,
+        description: Some("release".to_string()),
+    }
+}
+
+fn release_info() -> ReleaseInfo {
+    ReleaseInfo {
+        hash: HASH.to_string(),
+        version: "1.0.0".parse().unwrap(),
+        is_blob_exist: true,
+        downgrade_hash: None,
+        description: Some("release".to_string()),
+        family: None,
+    }
+}
@@ -1,10 +1,34 @@
+use near_sdk::serde_json::Value;
+use near_sdk::test_utils::{get_created_receipts, MockAction};
 use near_sdk::{AccountId, NearToken};
+use std::collections::BTreeMap;
 
-use crate::types::ReleaseInfo;
+use crate::types::{DeploymentInfo, ReleaseInfo};
 use crate::AuroraControllerFactory;
 
 #[macro_use]
 mod macros;
+mod batch;
+mod channel;
+mod delegation;
+mod deposit;
+mod downgrade;
+mod failure;
+mod family;
+mod fleet;
+mod keys;
+mod migration;
+mod pause;
+mod policy;
+mod preflight;
+mod proposal;
+mod rollout;
+mod storage;
+mod target;
+mod timelock;
+mod upgrade_method;
+mod upload;
+mod views;
 
 #[test]
 fn test_controller_version() {
@@ -18,7 +42,7 @@ fn test_adding_blob() {
     set_env!(
         predecessor_account_id: predecessor_account_id(),
         input: vec![1; 256],
-        attached_deposit: NearToken::from_yoctonear(1),
+        attached_deposit: NearToken::from_near(1),
     );
     let mut contract = AuroraControllerFactory::new(dao());
 
@@ -28,6 +52,7 @@ fn test_adding_blob() {
         true,
         None,
         None,
+        None,
     );
     contract.add_release_blob();
 
@@ -39,14 +64,15 @@ fn test_adding_blob() {
             version: "1.0.0".parse().unwrap(),
             is_blob_exist: true,
             downgrade_hash: None,
-            description: None
+            description: None,
+            family: None,
         }]
     );
 
     set_env!(
         predecessor_account_id: predecessor_account_id(),
         input: vec![2; 256],
-        attached_deposit: NearToken::from_yoctonear(1),
+        attached_deposit: NearToken::from_near(1),
     );
 
     contract.add_release_info(
@@ -55,6 +81,7 @@ fn test_adding_blob() {
         true,
         Some("2661920f2409dd6c8adeb0c44972959f232b6429afa913845d0fd95e7e768234".to_string()),
         None,
+        None,
     );
     contract.add_release_blob();
 
@@ -77,7 +104,8 @@ fn test_adding_blob() {
                 downgrade_hash: Some(
                     "2661920f2409dd6c8adeb0c44972959f232b6429afa913845d0fd95e7e768234".to_string()
                 ),
-                description: None
+                description: None,
+                family: None,
             }
         ]
     );
@@ -101,7 +129,7 @@ fn test_check_latest_release() {
     set_env!(
         predecessor_account_id: predecessor_account_id(),
         input: vec![1; 256],
-        attached_deposit: NearToken::from_yoctonear(1),
+        attached_deposit: NearToken::from_near(1),
     );
     let mut contract = AuroraControllerFactory::new(dao());
 
@@ -111,6 +139,7 @@ fn test_check_latest_release() {
         true,
         None,
         None,
+        None,
     );
     contract.add_release_blob();
 
@@ -123,7 +152,7 @@ fn test_check_latest_release() {
     set_env!(
         predecessor_account_id: predecessor_account_id(),
         input: vec![2; 256],
-        attached_deposit: NearToken::from_yoctonear(1),
+        attached_deposit: NearToken::from_near(1),
     );
 
     contract.add_release_info(
@@ -132,6 +161,7 @@ fn test_check_latest_release() {
         true,
         Some("2661920f2409dd6c8adeb0c44972959f232b6429afa913845d0fd95e7e768234".to_string()),
         None,
+        None,
     );
     contract.add_release_blob();
 
@@ -155,7 +185,7 @@ fn test_check_latest_release_hash_without_adding() {
 fn test_set_latest_with_lower_version() {
     set_env!(
         predecessor_account_id: predecessor_account_id(),
-        attached_deposit: NearToken::from_yoctonear(1),
+        attached_deposit: NearToken::from_near(1),
     );
     let mut contract = AuroraControllerFactory::new(dao());
 
@@ -165,6 +195,7 @@ fn test_set_latest_with_lower_version() {
         false,
         None,
         None,
+        None,
     );
     contract.add_release_info(
         "f5c22e35d04167e37913e7963ce033b1f3d17a924a4e6fe5fc95af1224051921".to_string(),
@@ -172,8 +203,13 @@ fn test_set_latest_with_lower_version() {
         true,
         Some("2661920f2409dd6c8adeb0c44972959f232b6429afa913845d0fd95e7e768234".to_string()),
         None,
+        None,
     );
 
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
     contract.set_latest_release(
         &"2661920f2409dd6c8adeb0c44972959f232b6429afa913845d0fd95e7e768234".to_owned(),
     );
@@ -184,7 +220,7 @@ fn test_set_latest_with_lower_version() {
 fn test_add_latest_with_lower_version() {
     set_env!(
         predecessor_account_id: predecessor_account_id(),
-        attached_deposit: NearToken::from_yoctonear(1),
+        attached_deposit: NearToken::from_near(1),
     );
 
     let mut contract = AuroraControllerFactory::new(dao());
@@ -195,6 +231,7 @@ fn test_add_latest_with_lower_version() {
         true,
         None,
         None,
+        None,
     );
 
     contract.add_release_info(
@@ -203,6 +240,7 @@ fn test_add_latest_with_lower_version() {
         true,
         None,
         None,
+        None,
     );
 }
 
@@ -218,6 +256,44 @@ fn test_use_not_allowed_pause_method() {
     contract.delegate_pause(new_engine(), Some("some_pause_method".to_string()), None);
 }
 
+#[test]
+#[should_panic = "release with hash: 2661920f2409dd6c8adeb0c44972959f232b6429afa913845d0fd95e7e768234 is referenced by deployments: silo-4.near; the latest release"]
+fn test_remove_referenced_release() {
+    let mut contract = batch::create_contract();
+    let hash = "2661920f2409dd6c8adeb0c44972959f232b6429afa913845d0fd95e7e768234".to_string();
+
+    contract.add_deployment_info(
+        "silo-4.near".parse().unwrap(),
+        DeploymentInfo {
+            hash: hash.clone(),
+            version: "1.0.0".parse().unwrap(),
+            deployment_time: 0,
+            upgrade_times: BTreeMap::new(),
+            init_args: String::new(),
+            last_error: None,
+            channel: None,
+            family: None,
+            key_policy: None,
+            access_keys: vec![],
+        },
+    );
+    contract.remove_release(&hash, None);
+}
+
+#[test]
+fn test_force_remove_referenced_release() {
+    let mut contract = batch::create_contract();
+    let hash = "2661920f2409dd6c8adeb0c44972959f232b6429afa913845d0fd95e7e768234".to_string();
+
+    let references = contract.get_release_references(&hash);
+    assert!(references.is_latest);
+    assert!(references.deployments.is_empty());
+
+    contract.remove_release(&hash, Some(true));
+    assert!(contract.get_releases().is_empty());
+    assert!(contract.get_release_references(&hash).is_empty());
+}
+
 fn dao() -> Option<AccountId> {
     "alice.near".parse().ok()
 }
@@ -229,3 +305,24 @@ fn predecessor_account_id() -> AccountId {
 fn new_engine() -> AccountId {
     "new_engine".parse().unwrap()
 }
+
+/// Returns the actions of the promises created by the last call.
+fn created_actions() -> Vec<MockAction> {
+    get_created_receipts()
+        .into_iter()
+        .flat_map(|receipt| receipt.actions)
+        .collect()
+}
+
+/// Returns the JSON arguments of the function calls with the method name created by the last call.
+fn function_call_args(method: &str) -> Vec<Value> {
+    created_actions()
+        .into_iter()
+        .filter_map(|action| match action {
+            MockAction::FunctionCallWeight {
+                method_name, args, ..
+            } if method_name == method.as_bytes() => near_sdk::serde_json::from_slice(&args).ok(),
+            _ => None,
+        })
+        .collect()
+}
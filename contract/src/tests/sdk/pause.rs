@@ -0,0 +1,105 @@
+use near_sdk::{AccountId, NearToken};
+
+use super::batch::create_contract;
+use super::predecessor_account_id;
+use crate::types::PauseMethods;
+
+#[test]
+fn test_pause_methods_of_deployment() {
+    let mut contract = create_contract();
+    let silo_1: AccountId = "silo-1.near".parse().unwrap();
+    let silo_2: AccountId = "silo-2.near".parse().unwrap();
+    let bridge = PauseMethods {
+        pause: vec!["pause".to_string()],
+        unpause: vec!["unpause".to_string()],
+        ..Default::default()
+    };
+    let token = PauseMethods {
+        pause: vec!["pause_transfers".to_string()],
+        unpause: vec!["resume_transfers".to_string()],
+        ..Default::default()
+    };
+
+    assert_eq!(contract.get_pause_methods(&silo_1), PauseMethods::default());
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.set_family_pause_methods(None, Some(bridge.clone()));
+    contract.set_deployment_pause_methods(silo_2.clone(), Some(token.clone()));
+
+    assert_eq!(contract.get_pause_methods(&silo_1), bridge);
+    assert_eq!(contract.get_pause_methods(&silo_2), token);
+
+    contract.delegate_pause(silo_1.clone(), None, None);
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.delegate_unpause(silo_1, Some("unpause".to_string()), None);
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.delegate_unpause(silo_2.clone(), None, None);
+
+    contract.set_family_pause_methods(None, None);
+    contract.set_deployment_pause_methods(silo_2.clone(), None);
+    assert_eq!(contract.get_pause_methods(&silo_2), PauseMethods::default());
+}
+
+#[test]
+fn test_delegate_unpause_by_default_method() {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.delegate_unpause("silo-1.near".parse().unwrap(), None, None);
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.delegate_unpause(
+        "silo-1.near".parse().unwrap(),
+        Some("pa_unpause_feature".to_string()),
+        Some(near_sdk::serde_json::json!({"key": "ALL"})),
+    );
+}
+
+#[test]
+#[should_panic = "unpause method: pause_contract is not allowed"]
+fn test_use_not_allowed_unpause_method() {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.delegate_unpause(
+        "silo-1.near".parse().unwrap(),
+        Some("pause_contract".to_string()),
+        None,
+    );
+}
+
+#[test]
+#[should_panic = "list of the unpause methods can't be empty"]
+fn test_set_empty_unpause_methods() {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.set_family_pause_methods(
+        Some("bridge".to_string()),
+        Some(PauseMethods {
+            pause: vec!["pause".to_string()],
+            unpause: vec![],
+            ..Default::default()
+        }),
+    );
+}
@@ -0,0 +1,143 @@
+use near_sdk::{AccountId, NearToken};
+
+use super::batch::create_contract;
+use super::predecessor_account_id;
+use crate::types::UpgradePolicy;
+use crate::utils::hash_256;
+use crate::AuroraControllerFactory;
+
+#[test]
+#[should_panic = "upgrade from version: 0.9.0 to: 2.0.0 skips major version"]
+fn test_upgrade_with_major_version_skip() {
+    let mut contract = create_contract_with_release("2.0.0");
+    let policy = UpgradePolicy {
+        forbid_major_skip: true,
+        ..Default::default()
+    };
+
+    contract.set_family_upgrade_policy(None, Some(policy.clone()));
+    assert_eq!(
+        contract.get_upgrade_policy(&hash_256([2_u8; 256])),
+        Some(policy)
+    );
+
+    contract.upgrade(
+        "silo-1.near".parse().unwrap(),
+        Some(hash_256([2_u8; 256])),
+        None,
+    );
+}
+
+#[test]
+#[should_panic = "checkpoint release with version: 1.0.0 should be deployed first"]
+fn test_upgrade_past_checkpoint() {
+    let mut contract = create_contract_with_release("1.1.0");
+
+    contract.set_release_upgrade_policy(
+        hash_256([2_u8; 256]),
+        Some(UpgradePolicy {
+            checkpoints: vec!["1.0.0".parse().unwrap()],
+            ..Default::default()
+        }),
+    );
+    contract.upgrade(
+        "silo-1.near".parse().unwrap(),
+        Some(hash_256([2_u8; 256])),
+        None,
+    );
+}
+
+#[test]
+#[should_panic = "pre-release version: 1.1.0-rc.1 can't be deployed to the production contract"]
+fn test_upgrade_to_pre_release() {
+    let mut contract = create_contract_with_release("1.1.0-rc.1");
+
+    contract.set_family_upgrade_policy(
+        None,
+        Some(UpgradePolicy {
+            forbid_pre_release: true,
+            ..Default::default()
+        }),
+    );
+    contract.upgrade(
+        "silo-3.near".parse().unwrap(),
+        Some(hash_256([2_u8; 256])),
+        None,
+    );
+}
+
+#[test]
+#[should_panic = "pre-release version: 1.1.0-rc.1 can't be deployed to the production contract"]
+fn test_upgrade_production_contract_of_channel_to_pre_release() {
+    let mut contract = create_contract_with_pre_release_policy();
+    let silo: AccountId = "silo-3.near".parse().unwrap();
+
+    contract.set_channel_release("beta".to_string(), hash_256([2_u8; 256]));
+    contract.set_deployment_channel(silo.clone(), Some("beta".to_string()));
+    assert!(contract.is_production(&silo));
+
+    contract.upgrade(silo, None, None);
+}
+
+#[test]
+fn test_upgrade_non_production_contract_to_pre_release() {
+    let mut contract = create_contract_with_pre_release_policy();
+    let silo: AccountId = "silo-3.near".parse().unwrap();
+
+    contract.set_family_production(None, Some(false));
+    assert!(!contract.is_production(&silo));
+    contract.set_deployment_production(silo.clone(), Some(true));
+    assert!(contract.is_production(&silo));
+    contract.set_deployment_production(silo.clone(), Some(false));
+
+    contract.upgrade(silo, Some(hash_256([2_u8; 256])), None);
+}
+
+fn create_contract_with_pre_release_policy() -> AuroraControllerFactory {
+    let mut contract = create_contract_with_release("1.1.0-rc.1");
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        input: vec![2; 256],
+        attached_deposit: NearToken::from_near(1),
+    );
+    contract.add_release_blob();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.set_family_upgrade_policy(
+        None,
+        Some(UpgradePolicy {
+            forbid_pre_release: true,
+            ..Default::default()
+        }),
+    );
+
+    contract
+}
+
+fn create_contract_with_release(version: &str) -> AuroraControllerFactory {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_near(1),
+    );
+    contract.add_release_info(
+        hash_256([2_u8; 256]),
+        version.parse().unwrap(),
+        false,
+        None,
+        None,
+        None,
+    );
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+
+    contract
+}
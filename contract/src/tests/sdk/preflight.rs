@@ -0,0 +1,165 @@
+use near_sdk::{Gas, NearToken};
+
+use super::batch::create_contract;
+use super::predecessor_account_id;
+use crate::types::{DeployOptions, DeployTarget, DeploymentAction, DepositSplit, Preflight};
+use crate::utils::hash_256;
+
+#[test]
+fn test_preflight_upgrade() {
+    let contract = create_contract();
+    let preflight = contract.preflight_upgrade("silo-1.near".parse().unwrap(), None, None);
+
+    let Preflight::Ready(plan) = preflight else {
+        panic!("unexpected preflight result: {preflight:?}");
+    };
+    assert_eq!(plan.action, DeploymentAction::Upgrade);
+    assert_eq!(plan.release_info.hash, hash_256([1_u8; 256]));
+    assert_eq!(plan.current_version, "0.9.0".parse().ok());
+    assert_eq!(plan.required_deposit, NearToken::from_yoctonear(1));
+    assert_eq!(plan.attached_gas, Gas::from_tgas(185));
+}
+
+#[test]
+fn test_preflight_upgrade_with_several_reasons() {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_near(1),
+    );
+    contract.add_release_info(
+        hash_256([2_u8; 256]),
+        "1.1.0".parse().unwrap(),
+        false,
+        None,
+        None,
+        None,
+    );
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.set_upgrade_delay("silo-1.near".parse().unwrap(), Some(1_000));
+
+    let preflight = contract.preflight_upgrade(
+        "silo-1.near".parse().unwrap(),
+        Some(hash_256([2_u8; 256])),
+        None,
+    );
+    assert_eq!(
+        preflight,
+        Preflight::Rejected {
+            reasons: vec![
+                "upgrade of the contract: silo-1.near is time-locked, use `schedule_upgrade`"
+                    .to_string(),
+                format!(
+                    "blob doesn't exist for hash: {} and version: 1.1.0",
+                    hash_256([2_u8; 256])
+                ),
+            ]
+        }
+    );
+}
+
+#[test]
+fn test_preflight_deploy() {
+    let contract = create_contract();
+
+    assert_eq!(
+        contract.preflight_deploy("silo-1.near".parse().unwrap(), None, None, None, None),
+        Preflight::Rejected {
+            reasons: vec!["silo-1.near is already deployed".to_string()]
+        }
+    );
+
+    let preflight =
+        contract.preflight_deploy("silo-4.near".parse().unwrap(), None, None, None, None);
+    let Preflight::Ready(plan) = preflight else {
+        panic!("unexpected preflight result: {preflight:?}");
+    };
+    assert_eq!(plan.release_info.version, "1.0.0".parse().unwrap());
+    assert_eq!(plan.action, DeploymentAction::Deploy);
+    assert_eq!(
+        plan.required_deposit,
+        near_sdk::env::storage_byte_cost().saturating_mul(256)
+    );
+    assert_eq!(plan.attached_gas, Gas::from_tgas(110));
+}
+
+#[test]
+fn test_preflight_deploy_with_options() {
+    let contract = create_contract();
+    let deposit_split = DepositSplit {
+        account_balance: NearToken::from_near(1),
+        init_deposit: NearToken::from_near(2),
+        init_gas: Some(Gas::from_tgas(50)),
+    };
+
+    let preflight = contract.preflight_deploy(
+        "silo-4.near".parse().unwrap(),
+        None,
+        None,
+        None,
+        Some(DeployOptions {
+            deposit_split: Some(deposit_split),
+            ..Default::default()
+        }),
+    );
+    let Preflight::Ready(plan) = preflight else {
+        panic!("unexpected preflight result: {preflight:?}");
+    };
+    assert_eq!(plan.required_deposit, NearToken::from_near(3));
+    assert_eq!(plan.attached_gas, Gas::from_tgas(60));
+
+    let preflight = contract.preflight_deploy(
+        "silo.near".parse().unwrap(),
+        None,
+        None,
+        None,
+        Some(DeployOptions {
+            target: Some(DeployTarget::Existing),
+            ..Default::default()
+        }),
+    );
+    let Preflight::Ready(plan) = preflight else {
+        panic!("unexpected preflight result: {preflight:?}");
+    };
+    assert_eq!(plan.required_deposit, NearToken::from_yoctonear(1));
+    assert_eq!(plan.attached_gas, Gas::from_tgas(190));
+}
+
+#[test]
+fn test_preflight_deploy_with_invalid_options() {
+    let contract = create_contract();
+
+    let preflight = contract.preflight_deploy(
+        "silo-4.near".parse().unwrap(),
+        None,
+        None,
+        None,
+        Some(DeployOptions {
+            target: Some(DeployTarget::AccountCreator),
+            deposit_split: Some(DepositSplit {
+                account_balance: NearToken::from_yoctonear(1),
+                init_deposit: NearToken::from_near(1),
+                init_gas: None,
+            }),
+            ..Default::default()
+        }),
+    );
+    assert_eq!(
+        preflight,
+        Preflight::Rejected {
+            reasons: vec![
+                "account creator isn't set".to_string(),
+                format!(
+                    "account balance: 1 doesn't cover the storage of the code: {}",
+                    near_sdk::env::storage_byte_cost()
+                        .saturating_mul(256)
+                        .as_yoctonear()
+                ),
+            ]
+        }
+    );
+}
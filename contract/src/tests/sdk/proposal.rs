@@ -0,0 +1,201 @@
+use near_plugins::AccessControllable;
+use near_sdk::test_utils::{get_created_receipts, MockAction};
+use near_sdk::{AccountId, Gas, NearToken, PromiseOrValue, PublicKey};
+
+use super::{dao, predecessor_account_id};
+use crate::types::{FunctionCallArgs, ProposalAction, ProposalPolicy};
+use crate::utils::hash_256;
+use crate::{AuroraControllerFactory, Role};
+
+const DURATION: u64 = 3_600_000_000_000;
+
+#[test]
+#[should_panic = "the action could be executed by the approved proposal only"]
+fn test_attach_full_access_key_with_proposal_policy() {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.attach_full_access_key(public_key());
+}
+
+#[test]
+fn test_approve_proposal() {
+    let mut contract = create_contract();
+    let bob: AccountId = "bob.near".parse().unwrap();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    let proposal_id = contract.create_proposal(ProposalAction::AttachFullAccessKey {
+        public_key: public_key(),
+    });
+    let result = contract.approve_proposal(proposal_id);
+    assert!(matches!(result, PromiseOrValue::Value(())));
+    assert!(!contract.get_proposal(proposal_id).unwrap().is_executed);
+
+    set_env!(
+        predecessor_account_id: bob.clone(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    let result = contract.approve_proposal(proposal_id);
+    assert!(matches!(result, PromiseOrValue::Promise(_)));
+
+    let proposal = contract.get_proposal(proposal_id).unwrap();
+    assert!(proposal.is_executed);
+    assert!(proposal.approvals.contains(&bob));
+}
+
+#[test]
+#[should_panic = "proposal has been expired"]
+fn test_approve_expired_proposal() {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        block_timestamp: 100,
+    );
+    let proposal_id = contract.create_proposal(ProposalAction::AttachFullAccessKey {
+        public_key: public_key(),
+    });
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        block_timestamp: DURATION + 100,
+    );
+    contract.approve_proposal(proposal_id);
+}
+
+#[test]
+#[should_panic = "threshold: 3 of the proposal policy exceeds the number of the members: 2"]
+fn test_set_proposal_policy_with_unreachable_threshold() {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.create_proposal(ProposalAction::SetProposalPolicy {
+        policy: Some(ProposalPolicy {
+            roles: vec![Role::DAO, Role::Executor],
+            threshold: 3,
+            duration: DURATION,
+        }),
+    });
+}
+
+#[test]
+#[should_panic = "the action could be executed by the approved proposal only"]
+fn test_schedule_unrestricted_upgrade_with_proposal_policy() {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.schedule_upgrade("silo.near".parse().unwrap(), None, None, Some(true));
+}
+
+#[test]
+#[should_panic = "upgrade of the contract: silo-1.near is time-locked, use `schedule_upgrade`"]
+fn test_execute_unrestricted_upgrade_after_setting_delay() {
+    let mut contract = super::batch::create_contract();
+    let silo_1: AccountId = "silo-1.near".parse().unwrap();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    set_proposal_policy(&mut contract);
+    let proposal_id = contract.create_proposal(ProposalAction::UnrestrictedUpgrade {
+        contract_id: silo_1.clone(),
+        hash: hash_256([1_u8; 256]),
+        state_migration_gas: None,
+    });
+    contract.approve_proposal(proposal_id);
+    contract.set_upgrade_delay(silo_1, Some(DURATION));
+
+    set_env!(
+        predecessor_account_id: "bob.near".parse::<AccountId>().unwrap(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.approve_proposal(proposal_id);
+}
+
+#[test]
+fn test_refund_surplus_of_delegated_execution() {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_near(3),
+    );
+    let proposal_id = contract.create_proposal(ProposalAction::DelegateExecution {
+        receiver_id: "silo.near".parse().unwrap(),
+        actions: vec![FunctionCallArgs {
+            function_name: "set_fee".to_string(),
+            arguments: vec![].into(),
+            amount: NearToken::from_near(1),
+            gas: Gas::from_tgas(10),
+        }],
+    });
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.approve_proposal(proposal_id);
+
+    set_env!(
+        predecessor_account_id: "bob.near".parse::<AccountId>().unwrap(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.approve_proposal(proposal_id);
+    let transfers: Vec<_> = get_created_receipts()
+        .into_iter()
+        .flat_map(|receipt| {
+            let receiver_id = receipt.receiver_id;
+            receipt
+                .actions
+                .into_iter()
+                .filter_map(move |action| match action {
+                    MockAction::Transfer { deposit, .. } => Some((receiver_id.clone(), deposit)),
+                    _ => None,
+                })
+        })
+        .collect();
+    assert_eq!(
+        transfers,
+        vec![(predecessor_account_id(), NearToken::from_near(2))]
+    );
+}
+
+fn create_contract() -> AuroraControllerFactory {
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    let mut contract = AuroraControllerFactory::new(dao());
+    set_proposal_policy(&mut contract);
+    contract
+}
+
+fn set_proposal_policy(contract: &mut AuroraControllerFactory) {
+    contract.acl_grant_role(Role::DAO.into(), "bob.near".parse().unwrap());
+    contract.set_proposal_policy(ProposalPolicy {
+        roles: vec![Role::DAO],
+        threshold: 2,
+        duration: DURATION,
+    });
+}
+
+fn public_key() -> PublicKey {
+    "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp"
+        .parse()
+        .unwrap()
+}
@@ -0,0 +1,209 @@
+use near_sdk::{AccountId, Gas, NearToken};
+use std::collections::BTreeMap;
+
+use super::batch::create_contract;
+use super::predecessor_account_id;
+use crate::types::{DeploymentInfo, UpgradeStatus, WaveSelection};
+use crate::utils::hash_256;
+
+#[test]
+fn test_create_rollout() {
+    let mut contract = create_contract();
+    let silo_1: AccountId = "silo-1.near".parse().unwrap();
+    let silo_2: AccountId = "silo-2.near".parse().unwrap();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    let rollout_id = contract.create_rollout(
+        None,
+        vec![
+            WaveSelection::Contracts(vec![silo_2.clone()]),
+            WaveSelection::Percentage(100),
+        ],
+        None,
+        None,
+    );
+
+    // The contract `silo-3.near` is already upgraded to the version of the release.
+    let rollout = contract.get_rollout(rollout_id).unwrap();
+    assert_eq!(rollout.waves.len(), 2);
+    assert_eq!(rollout.waves[0].contract_ids, vec![silo_2]);
+    assert_eq!(rollout.waves[1].contract_ids, vec![silo_1]);
+    assert_eq!(rollout.next_wave(), Some(0));
+}
+
+#[test]
+#[should_panic = "current wave of the rollout has failed upgrades"]
+fn test_advance_rollout_with_failed_wave() {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        prepaid_gas: Gas::from_tgas(300),
+    );
+    let rollout_id = contract.create_rollout(
+        None,
+        vec![
+            WaveSelection::Contracts(vec!["silo-1.near".parse().unwrap()]),
+            WaveSelection::Percentage(100),
+        ],
+        None,
+        None,
+    );
+    let batch_id = contract.advance_rollout(rollout_id);
+    contract.update_batch_upgrade_status(batch_id, &"silo-1.near".parse().unwrap(), false);
+
+    contract.advance_rollout(rollout_id);
+}
+
+#[test]
+fn test_rollback_rollout_wave() {
+    let mut contract = create_contract();
+    let silo_1: AccountId = "silo-1.near".parse().unwrap();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        prepaid_gas: Gas::from_tgas(300),
+    );
+    let rollout_id = contract.create_rollout(
+        None,
+        vec![WaveSelection::Contracts(vec![silo_1.clone()])],
+        None,
+        None,
+    );
+    let batch_id = contract.advance_rollout(rollout_id);
+    assert_eq!(
+        contract.get_batch_upgrade(batch_id).unwrap().targets[&silo_1],
+        UpgradeStatus::InProgress
+    );
+    contract.update_batch_upgrade_status(batch_id, &silo_1, true);
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        prepaid_gas: Gas::from_tgas(300),
+    );
+    let batch_ids = contract.rollback_rollout_wave(rollout_id);
+    assert_eq!(batch_ids.len(), 1);
+
+    let batch = contract.get_batch_upgrade(batch_ids[0]).unwrap();
+    assert!(batch.is_downgrade);
+    assert_eq!(batch.hash, "");
+    assert!(batch.targets.contains_key(&silo_1));
+    assert!(contract.get_rollout(rollout_id).unwrap().is_rolled_back());
+}
+
+#[test]
+fn test_rollback_rollout_wave_of_time_locked_contract() {
+    let mut contract = create_contract();
+    let silo_4: AccountId = "silo-4.near".parse().unwrap();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        input: vec![2; 256],
+        attached_deposit: NearToken::from_near(1),
+    );
+    contract.add_release_info(
+        hash_256([2_u8; 256]),
+        "0.9.0".parse().unwrap(),
+        false,
+        None,
+        None,
+        None,
+    );
+    contract.add_release_blob();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        prepaid_gas: Gas::from_tgas(300),
+    );
+    contract.add_deployment_info(
+        silo_4.clone(),
+        DeploymentInfo {
+            hash: hash_256([2_u8; 256]),
+            version: "0.9.0".parse().unwrap(),
+            deployment_time: 0,
+            upgrade_times: BTreeMap::new(),
+            init_args: String::new(),
+            last_error: None,
+            channel: None,
+            family: None,
+            key_policy: None,
+            access_keys: vec![],
+        },
+    );
+    let rollout_id = contract.create_rollout(
+        None,
+        vec![WaveSelection::Contracts(vec![silo_4.clone()])],
+        None,
+        None,
+    );
+    let batch_id = contract.advance_rollout(rollout_id);
+    contract.update_batch_upgrade_status(batch_id, &silo_4, true);
+    contract.add_deployment_info(
+        silo_4.clone(),
+        DeploymentInfo {
+            hash: hash_256([1_u8; 256]),
+            version: "1.0.0".parse().unwrap(),
+            deployment_time: 0,
+            upgrade_times: BTreeMap::new(),
+            init_args: String::new(),
+            last_error: None,
+            channel: None,
+            family: None,
+            key_policy: None,
+            access_keys: vec![],
+        },
+    );
+
+    // Rollbacks and downgrades aren't time-locked, so the broken release could be reverted at once.
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        prepaid_gas: Gas::from_tgas(300),
+    );
+    contract.set_upgrade_delay(silo_4.clone(), Some(3_600_000_000_000));
+    let batch_ids = contract.rollback_rollout_wave(rollout_id);
+
+    let batch = contract.get_batch_upgrade(batch_ids[0]).unwrap();
+    assert_eq!(batch.hash, hash_256([2_u8; 256]));
+    assert_eq!(batch.targets[&silo_4], UpgradeStatus::InProgress);
+}
+
+#[test]
+fn test_rollback_rollout_wave_to_higher_version() {
+    let mut contract = create_contract();
+    let silo_1: AccountId = "silo-1.near".parse().unwrap();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        prepaid_gas: Gas::from_tgas(300),
+    );
+    let rollout_id = contract.create_rollout(
+        None,
+        vec![WaveSelection::Contracts(vec![silo_1.clone()])],
+        None,
+        None,
+    );
+    let batch_id = contract.advance_rollout(rollout_id);
+    contract.update_batch_upgrade_status(batch_id, &silo_1, true);
+
+    // The rollback passes the checks of `downgrade`, so the unknown previous hash is rejected.
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        prepaid_gas: Gas::from_tgas(300),
+    );
+    let batch_ids = contract.rollback_rollout_wave(rollout_id);
+    let batch = contract.get_batch_upgrade(batch_ids[0]).unwrap();
+    assert!(matches!(
+        &batch.targets[&silo_1],
+        UpgradeStatus::Failed(reason) if reason.contains("release info doesn't exist for hash")
+    ));
+}
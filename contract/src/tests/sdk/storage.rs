@@ -0,0 +1,133 @@
+use near_sdk::test_utils::get_logs;
+use near_sdk::{env, AccountId, NearToken};
+
+use super::{dao, predecessor_account_id};
+use crate::utils::hash_256;
+use crate::AuroraControllerFactory;
+
+#[test]
+fn test_storage_stake_of_release() {
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        input: vec![1; 256],
+        attached_deposit: NearToken::from_near(1),
+    );
+    let mut contract = AuroraControllerFactory::new(dao());
+    let hash = hash_256([1_u8; 256]);
+
+    contract.add_release_info(
+        hash.clone(),
+        "1.0.0".parse().unwrap(),
+        false,
+        None,
+        None,
+        None,
+    );
+    contract.add_release_blob();
+
+    let stake = contract.get_storage_stake(&hash).unwrap();
+    let release_info_stake = stake.release_info.unwrap();
+    let blob_stake = stake.blob.unwrap();
+
+    assert_eq!(release_info_stake.account_id, predecessor_account_id());
+    assert!(!release_info_stake.amount.is_zero());
+    assert_eq!(blob_stake.account_id, predecessor_account_id());
+    assert!(blob_stake.amount >= env::storage_byte_cost().saturating_mul(256));
+    assert!(blob_stake.amount < NearToken::from_near(1));
+}
+
+#[test]
+fn test_storage_stake_of_latest_release() {
+    let stakes = [None, Some("silo".to_string())].map(|family| {
+        [false, true].map(|is_latest| {
+            set_env!(
+                predecessor_account_id: predecessor_account_id(),
+                attached_deposit: NearToken::from_near(1),
+            );
+            let mut contract = AuroraControllerFactory::new(dao());
+            let hash = hash_256([1_u8; 256]);
+
+            contract.add_release_info(
+                hash.clone(),
+                "1.0.0".parse().unwrap(),
+                is_latest,
+                None,
+                None,
+                family.clone(),
+            );
+            contract
+                .get_storage_stake(&hash)
+                .and_then(|stake| stake.release_info)
+                .unwrap()
+                .amount
+        })
+    });
+
+    for [stake, latest_stake] in stakes {
+        assert!(latest_stake > stake);
+    }
+}
+
+#[test]
+#[should_panic = "doesn't cover the storage cost"]
+fn test_add_release_blob_with_small_deposit() {
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        input: vec![1; 256],
+        attached_deposit: NearToken::from_near(1),
+    );
+    let mut contract = AuroraControllerFactory::new(dao());
+    contract.add_release_info(
+        hash_256([1_u8; 256]),
+        "1.0.0".parse().unwrap(),
+        false,
+        None,
+        None,
+        None,
+    );
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        input: vec![1; 256],
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.add_release_blob();
+}
+
+#[test]
+fn test_refund_storage_stake_to_treasury() {
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        input: vec![1; 256],
+        attached_deposit: NearToken::from_near(1),
+    );
+    let mut contract = AuroraControllerFactory::new(dao());
+    let hash = hash_256([1_u8; 256]);
+
+    contract.add_release_info(
+        hash.clone(),
+        "1.0.0".parse().unwrap(),
+        false,
+        None,
+        None,
+        None,
+    );
+    contract.add_release_blob();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    let treasury: AccountId = "treasury.near".parse().unwrap();
+    contract.set_storage_treasury(Some(treasury.clone()));
+    assert_eq!(contract.get_storage_treasury(), Some(treasury));
+
+    contract.remove_release(&hash, None);
+    assert!(contract.get_storage_stake(&hash).is_none());
+
+    let refunds = get_logs()
+        .into_iter()
+        .filter(|log| log.contains("refund_storage_stake") && log.contains("treasury.near"))
+        .count();
+    assert_eq!(refunds, 2);
+}
@@ -0,0 +1,273 @@
+use near_sdk::serde_json::{json, Value};
+use near_sdk::{AccountId, NearToken, PromiseOrValue, PromiseResult};
+
+use super::batch::create_contract;
+use super::{function_call_args, predecessor_account_id};
+use crate::types::{DeployOptions, DeployTarget, DepositSplit, FunctionCallArgs, KeyPolicy};
+use crate::Role;
+
+#[test]
+fn test_deploy_by_account_creator() {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.set_account_creator(Some("near".parse().unwrap()));
+    assert_eq!(contract.get_account_creator(), "near".parse().ok());
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_near(5),
+    );
+    contract.deploy(
+        "silo".parse().unwrap(),
+        "new".to_string(),
+        json!({}),
+        None,
+        None,
+        None,
+        Some(DeployOptions {
+            target: Some(DeployTarget::AccountCreator),
+            deposit_split: Some(DepositSplit {
+                account_balance: NearToken::from_near(3),
+                init_deposit: NearToken::from_near(2),
+                init_gas: None,
+            }),
+            ..Default::default()
+        }),
+    );
+
+    let args = function_call_args("finish_account_creation").pop().unwrap();
+    assert_eq!(
+        args["deposit"],
+        NearToken::from_near(5).as_yoctonear().to_string()
+    );
+    let init_call: FunctionCallArgs =
+        near_sdk::serde_json::from_value(args["init_call"].clone()).unwrap();
+    assert_eq!(init_call.amount, NearToken::from_near(2));
+
+    set_env_with_promise_results!(
+        vec![PromiseResult::Successful(b"true".to_vec())];
+        predecessor_account_id: predecessor_account_id(),
+    );
+    let _ = contract.finish_account_creation(
+        "silo".parse().unwrap(),
+        near_sdk::serde_json::from_value(args["deployment_info"].clone()).unwrap(),
+        predecessor_account_id(),
+        NearToken::from_near(5),
+        init_call,
+    );
+    assert_eq!(function_call_args("new"), vec![json!({})]);
+    let args = function_call_args("finish_deploy").pop().unwrap();
+    assert_eq!(
+        args["refundable_deposit"],
+        NearToken::from_near(2).as_yoctonear().to_string()
+    );
+}
+
+#[test]
+fn test_record_failed_account_creation() {
+    let mut contract = create_contract();
+    let silo: AccountId = "silo".parse().unwrap();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.set_account_creator(Some("near".parse().unwrap()));
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_near(5),
+    );
+    contract.deploy(
+        silo.clone(),
+        "new".to_string(),
+        json!({}),
+        None,
+        None,
+        None,
+        Some(DeployOptions {
+            target: Some(DeployTarget::AccountCreator),
+            deposit_split: Some(DepositSplit {
+                account_balance: NearToken::from_near(3),
+                init_deposit: NearToken::from_near(2),
+                init_gas: None,
+            }),
+            ..Default::default()
+        }),
+    );
+    let args = function_call_args("finish_account_creation").pop().unwrap();
+
+    set_env_with_promise_results!(
+        vec![PromiseResult::Successful(b"false".to_vec())];
+        predecessor_account_id: predecessor_account_id(),
+    );
+    let result = contract.finish_account_creation(
+        silo.clone(),
+        near_sdk::serde_json::from_value(args["deployment_info"].clone()).unwrap(),
+        predecessor_account_id(),
+        NearToken::from_near(5),
+        near_sdk::serde_json::from_value(args["init_call"].clone()).unwrap(),
+    );
+    assert!(matches!(result, PromiseOrValue::Value(())));
+    assert!(function_call_args("new").is_empty());
+    assert_eq!(
+        function_call_args("refund_returned_deposit"),
+        vec![json!({"contract_id": &silo})]
+    );
+
+    let failed_deployment = contract.get_failed_deployment(&silo).unwrap();
+    assert_eq!(failed_deployment.target, DeployTarget::AccountCreator);
+    assert_eq!(
+        failed_deployment.refundable_deposit,
+        NearToken::from_near(5)
+    );
+    assert!(contract.get_deployment(&silo).is_none());
+}
+
+#[test]
+#[should_panic = "account creator isn't set"]
+fn test_deploy_without_account_creator() {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_near(5),
+    );
+    contract.deploy(
+        "silo".parse().unwrap(),
+        "new".to_string(),
+        json!({}),
+        None,
+        None,
+        None,
+        Some(DeployOptions {
+            target: Some(DeployTarget::AccountCreator),
+            ..Default::default()
+        }),
+    );
+}
+
+#[test]
+#[should_panic = "key policy isn't supported for existing accounts"]
+fn test_deploy_to_existing_account_with_key_policy() {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.deploy(
+        "silo.near".parse().unwrap(),
+        String::new(),
+        Value::Null,
+        None,
+        None,
+        None,
+        Some(DeployOptions {
+            key_policy: Some(KeyPolicy::Locked),
+            target: Some(DeployTarget::Existing),
+            ..Default::default()
+        }),
+    );
+}
+
+#[test]
+#[should_panic = "init method and arguments aren't supported for existing accounts"]
+fn test_deploy_to_existing_account_with_init_args() {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.deploy(
+        "silo.near".parse().unwrap(),
+        "new".to_string(),
+        json!({"chain_id": 1313161554}),
+        None,
+        None,
+        None,
+        Some(existing_target()),
+    );
+}
+
+#[test]
+#[should_panic = "only DAO could deploy to existing accounts"]
+fn test_deploy_to_existing_account_by_deployer() {
+    let mut contract = create_contract();
+    let deployer: AccountId = "bob.near".parse().unwrap();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.acl_grant_role(Role::Deployer.into(), deployer.clone());
+
+    set_env!(
+        predecessor_account_id: deployer,
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.deploy(
+        "silo.near".parse().unwrap(),
+        String::new(),
+        Value::Null,
+        None,
+        None,
+        None,
+        Some(existing_target()),
+    );
+}
+
+#[test]
+fn test_record_failed_deployment_to_existing_account() {
+    let mut contract = create_contract();
+    let silo: AccountId = "silo.near".parse().unwrap();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.deploy(
+        silo.clone(),
+        String::new(),
+        Value::Null,
+        None,
+        None,
+        None,
+        Some(existing_target()),
+    );
+    let args = function_call_args("finish_deploy").pop().unwrap();
+    assert_eq!(args["deployment_info"]["init_args"], "");
+    assert_eq!(args["refundable_deposit"], "0");
+
+    set_env_with_promise_results!(
+        vec![PromiseResult::Failed];
+        predecessor_account_id: "controller.near".parse::<AccountId>().unwrap(),
+        current_account_id: "controller.near".parse::<AccountId>().unwrap(),
+    );
+    contract.finish_deploy(
+        silo.clone(),
+        near_sdk::serde_json::from_value(args["deployment_info"].clone()).unwrap(),
+        DeployTarget::Existing,
+        predecessor_account_id(),
+        NearToken::from_yoctonear(1),
+        NearToken::from_yoctonear(0),
+    );
+
+    let failed = contract.get_failed_deployment(&silo).unwrap();
+    assert_eq!(failed.target, DeployTarget::Existing);
+    assert_eq!(failed.deposit, NearToken::from_yoctonear(1));
+    assert!(failed.refundable_deposit.is_zero());
+    assert!(contract.get_deployment(&silo).is_none());
+}
+
+fn existing_target() -> DeployOptions {
+    DeployOptions {
+        target: Some(DeployTarget::Existing),
+        ..Default::default()
+    }
+}
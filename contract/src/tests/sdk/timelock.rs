@@ -0,0 +1,91 @@
+use near_sdk::{AccountId, Gas, NearToken};
+
+use super::batch::create_contract;
+use super::predecessor_account_id;
+use crate::types::UpgradeStatus;
+
+const DELAY: u64 = 3_600_000_000_000;
+
+#[test]
+#[should_panic = "upgrade of the contract: silo-1.near is time-locked, use `schedule_upgrade`"]
+fn test_upgrade_time_locked_contract() {
+    let mut contract = create_contract();
+    let silo: AccountId = "silo-1.near".parse().unwrap();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.set_upgrade_delay(silo.clone(), Some(DELAY));
+    contract.upgrade(silo, None, None);
+}
+
+#[test]
+#[should_panic = "upgrade of the contract: silo-1.near could be executed after: 3600000000100"]
+fn test_execute_scheduled_upgrade_before_delay() {
+    let mut contract = create_contract();
+    let silo: AccountId = "silo-1.near".parse().unwrap();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        block_timestamp: 100,
+    );
+    contract.set_upgrade_delay(silo.clone(), Some(DELAY));
+    contract.schedule_upgrade(silo.clone(), None, None, None);
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        block_timestamp: DELAY,
+    );
+    contract.execute_scheduled_upgrade(silo);
+}
+
+#[test]
+fn test_execute_scheduled_upgrade() {
+    let mut contract = create_contract();
+    let silo: AccountId = "silo-1.near".parse().unwrap();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        block_timestamp: 100,
+    );
+    contract.set_upgrade_delay(silo.clone(), Some(DELAY));
+    let scheduled_upgrade = contract.schedule_upgrade(silo.clone(), None, None, None);
+    assert_eq!(scheduled_upgrade.announced_at, 100);
+    assert_eq!(scheduled_upgrade.execute_after, DELAY + 100);
+    assert_eq!(
+        contract.get_scheduled_upgrade(&silo),
+        Some(scheduled_upgrade)
+    );
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        block_timestamp: DELAY + 100,
+    );
+    contract.execute_scheduled_upgrade(silo.clone());
+    assert!(contract.get_scheduled_upgrade(&silo).is_none());
+}
+
+#[test]
+fn test_batch_upgrade_of_time_locked_contract() {
+    let mut contract = create_contract();
+    let silo: AccountId = "silo-1.near".parse().unwrap();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+        prepaid_gas: Gas::from_tgas(300),
+    );
+    contract.set_upgrade_delay(silo.clone(), Some(DELAY));
+    let batch_id = contract.batch_upgrade(Some(vec![silo.clone()]), None, None, None, None);
+
+    let batch = contract.get_batch_upgrade(batch_id).unwrap();
+    assert!(matches!(
+        &batch.targets[&silo],
+        UpgradeStatus::Failed(reason) if reason.contains("is time-locked")
+    ));
+}
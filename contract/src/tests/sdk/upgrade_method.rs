@@ -0,0 +1,143 @@
+use near_sdk::serde_json::json;
+use near_sdk::test_utils::MockAction;
+use near_sdk::{env, AccountId, Gas, NearToken};
+
+use super::batch::create_contract;
+use super::{created_actions, function_call_args, predecessor_account_id};
+use crate::types::{UpgradeArgsEncoding, UpgradeMethod};
+use crate::utils::hash_256;
+
+#[test]
+fn test_upgrade_method_of_deployment() {
+    let mut contract = create_contract();
+    let silo_1: AccountId = "silo-1.near".parse().unwrap();
+    let silo_2: AccountId = "silo-2.near".parse().unwrap();
+    let custom = UpgradeMethod::Custom {
+        method_name: "update".to_string(),
+        encoding: UpgradeArgsEncoding::Raw,
+        attach_one_yocto: false,
+    };
+
+    assert_eq!(contract.get_upgrade_method(&silo_1), UpgradeMethod::Aurora);
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.set_family_upgrade_method(
+        None,
+        Some(UpgradeMethod::NearPlugins {
+            migration_method: Some("migrate".to_string()),
+        }),
+    );
+    contract.set_deployment_upgrade_method(silo_2.clone(), Some(custom.clone()));
+
+    assert_eq!(
+        contract.get_upgrade_method(&silo_1),
+        UpgradeMethod::NearPlugins {
+            migration_method: Some("migrate".to_string())
+        }
+    );
+    assert_eq!(contract.get_upgrade_method(&silo_2), custom);
+
+    contract.upgrade(
+        silo_1.clone(),
+        Some(hash_256([1_u8; 256])),
+        Some(10_000_000_000_000),
+    );
+    assert_eq!(
+        stage_code_args(),
+        vec![near_sdk::borsh::to_vec(&vec![1_u8; 256]).unwrap()]
+    );
+    assert_eq!(
+        function_call_args("up_deploy_code"),
+        vec![json!({
+            "hash": code_hash(),
+            "function_call_args": {
+                "function_name": "migrate",
+                "arguments": [],
+                "amount": "0",
+                "gas": "10000000000000",
+            },
+        })]
+    );
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.upgrade(silo_1, Some(hash_256([1_u8; 256])), None);
+    assert_eq!(stage_code_args().len(), 1);
+    assert_eq!(
+        function_call_args("up_deploy_code"),
+        vec![json!({"hash": code_hash(), "function_call_args": null})]
+    );
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.upgrade(silo_2.clone(), Some(hash_256([1_u8; 256])), None);
+    let update_calls: Vec<_> = created_actions()
+        .into_iter()
+        .filter_map(|action| match action {
+            MockAction::FunctionCallWeight {
+                method_name,
+                args,
+                attached_deposit,
+                prepaid_gas,
+                ..
+            } if method_name == b"update" => Some((args, attached_deposit, prepaid_gas)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        update_calls,
+        vec![(
+            vec![1_u8; 256],
+            NearToken::from_yoctonear(0),
+            Gas::from_tgas(180)
+        )]
+    );
+
+    contract.set_family_upgrade_method(None, None);
+    contract.set_deployment_upgrade_method(silo_2.clone(), None);
+    assert_eq!(contract.get_upgrade_method(&silo_2), UpgradeMethod::Aurora);
+}
+
+#[test]
+#[should_panic = "upgrade method name can't be empty"]
+fn test_set_upgrade_method_with_empty_name() {
+    let mut contract = create_contract();
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.set_family_upgrade_method(
+        None,
+        Some(UpgradeMethod::Custom {
+            method_name: String::new(),
+            encoding: UpgradeArgsEncoding::Borsh,
+            attach_one_yocto: true,
+        }),
+    );
+}
+
+/// Returns the borsh arguments of the `up_stage_code` calls created by the last call.
+fn stage_code_args() -> Vec<Vec<u8>> {
+    created_actions()
+        .into_iter()
+        .filter_map(|action| match action {
+            MockAction::FunctionCallWeight {
+                method_name, args, ..
+            } if method_name == b"up_stage_code" => Some(args),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns the `bs58` encoded hash of the code of the release used in the tests.
+fn code_hash() -> String {
+    near_sdk::bs58::encode(env::sha256_array(&[1_u8; 256])).into_string()
+}
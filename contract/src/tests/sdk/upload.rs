@@ -0,0 +1,124 @@
+use near_sdk::test_utils::MockAction;
+use near_sdk::NearToken;
+
+use super::{created_actions, dao, predecessor_account_id};
+use crate::utils::hash_256;
+use crate::AuroraControllerFactory;
+
+#[test]
+fn test_chunked_blob_upload() {
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_near(1),
+    );
+    let mut contract = AuroraControllerFactory::new(dao());
+    let blob = (0..=255).collect::<Vec<u8>>();
+    let hash = hash_256(&blob);
+
+    contract.add_release_info(
+        hash.clone(),
+        "1.0.0".parse().unwrap(),
+        true,
+        None,
+        None,
+        None,
+    );
+    contract.begin_release_blob_upload(hash.clone(), 256, 3);
+    assert_eq!(contract.get_missing_blob_chunks(&hash), vec![0, 1, 2]);
+
+    contract.add_release_blob_chunk(hash.clone(), 2, blob[200..].to_vec().into());
+    contract.add_release_blob_chunk(hash.clone(), 0, blob[..100].to_vec().into());
+    assert_eq!(contract.get_missing_blob_chunks(&hash), vec![1]);
+
+    contract.add_release_blob_chunk(hash.clone(), 1, blob[100..200].to_vec().into());
+    assert!(contract.get_missing_blob_chunks(&hash).is_empty());
+
+    contract.finalize_release_blob_upload(hash.clone());
+
+    let releases = contract.get_releases();
+    assert_eq!(releases.len(), 1);
+    assert_eq!(releases[0].hash, hash);
+    assert!(releases[0].is_blob_exist);
+}
+
+#[test]
+#[should_panic = "doesn't match the expected"]
+fn test_chunked_blob_upload_with_wrong_hash() {
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_near(1),
+    );
+    let mut contract = AuroraControllerFactory::new(dao());
+    let hash = hash_256([1_u8; 256]);
+
+    contract.add_release_info(
+        hash.clone(),
+        "1.0.0".parse().unwrap(),
+        true,
+        None,
+        None,
+        None,
+    );
+    contract.begin_release_blob_upload(hash.clone(), 256, 2);
+    contract.add_release_blob_chunk(hash.clone(), 0, vec![1; 128].into());
+    contract.add_release_blob_chunk(hash.clone(), 1, vec![2; 128].into());
+    contract.finalize_release_blob_upload(hash);
+}
+
+#[test]
+#[should_panic = "chunks: [1] haven't been uploaded yet"]
+fn test_finalize_chunked_blob_upload_with_missing_chunks() {
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_near(1),
+    );
+    let mut contract = AuroraControllerFactory::new(dao());
+    let hash = hash_256([1_u8; 256]);
+
+    contract.add_release_info(
+        hash.clone(),
+        "1.0.0".parse().unwrap(),
+        true,
+        None,
+        None,
+        None,
+    );
+    contract.begin_release_blob_upload(hash.clone(), 256, 2);
+    contract.add_release_blob_chunk(hash.clone(), 0, vec![1; 128].into());
+    contract.finalize_release_blob_upload(hash);
+}
+
+#[test]
+fn test_remove_release_with_started_blob_upload() {
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_near(1),
+    );
+    let mut contract = AuroraControllerFactory::new(dao());
+    let hash = hash_256([1_u8; 256]);
+
+    contract.add_release_info(
+        hash.clone(),
+        "1.0.0".parse().unwrap(),
+        true,
+        None,
+        None,
+        None,
+    );
+    contract.begin_release_blob_upload(hash.clone(), 256, 2);
+    contract.add_release_blob_chunk(hash.clone(), 0, vec![1; 128].into());
+    let deposit = contract.blob_uploads.get(&hash).unwrap().deposit;
+    assert!(!deposit.is_zero());
+
+    set_env!(
+        predecessor_account_id: predecessor_account_id(),
+        attached_deposit: NearToken::from_yoctonear(1),
+    );
+    contract.remove_release(&hash, None);
+
+    assert!(contract.blob_uploads.get(&hash).is_none());
+    assert!(contract.blob_chunks.get(&(hash, 0)).is_none());
+    assert!(created_actions().into_iter().any(
+        |action| matches!(action, MockAction::Transfer { deposit: amount, .. } if amount == deposit)
+    ));
+}
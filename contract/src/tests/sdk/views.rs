@@ -0,0 +1,42 @@
+use super::batch::create_contract;
+use crate::types::{DeploymentFilter, ReleaseFilter};
+
+#[test]
+fn test_get_deployments_paginated() {
+    let contract = create_contract();
+
+    let page = contract.get_deployments_paginated(Some(1), Some(1), None);
+    assert_eq!(page.len(), 1);
+    assert!(page.contains_key(&"silo-2.near".parse().unwrap()));
+
+    let filter = DeploymentFilter {
+        max_version: "1.0.0".parse().ok(),
+        ..Default::default()
+    };
+    let page = contract.get_deployments_paginated(None, None, Some(filter.clone()));
+    assert_eq!(page.len(), 2);
+    assert!(!page.contains_key(&"silo-3.near".parse().unwrap()));
+
+    assert_eq!(contract.get_deployments_count(None), 3);
+    assert_eq!(contract.get_deployments_count(Some(filter)), 2);
+}
+
+#[test]
+fn test_get_releases_paginated() {
+    let contract = create_contract();
+
+    assert_eq!(contract.get_releases_paginated(None, None, None).len(), 1);
+    assert!(contract
+        .get_releases_paginated(Some(1), None, None)
+        .is_empty());
+
+    let filter = ReleaseFilter {
+        is_blob_exist: Some(false),
+        ..Default::default()
+    };
+    assert!(contract
+        .get_releases_paginated(None, None, Some(filter.clone()))
+        .is_empty());
+    assert_eq!(contract.get_releases_count(None), 1);
+    assert_eq!(contract.get_releases_count(Some(filter)), 0);
+}
@@ -3,11 +3,15 @@ use near_sdk::serde_json::json;
 use near_sdk::Gas;
 use near_workspaces::types::NearToken;
 use near_workspaces::{Account, AccountId, Contract};
+use std::collections::BTreeMap;
 use std::str::FromStr;
 
 use super::utils;
 use crate::tests::{BLOB_3_6_4, HASH_3_6_4};
-use crate::types::FunctionCallArgs;
+use crate::types::{
+    ExecutionRecord, ExecutionStatus, ExecutionStepResult, ExecutionStepStatus, FleetPause,
+    FunctionCallArgs, PauseStatus,
+};
 
 #[tokio::test]
 async fn test_delegate_execution() {
@@ -39,6 +43,133 @@ async fn test_delegate_execution() {
         .result;
     let owner = AccountId::from_str(&String::from_utf8(bytes).unwrap()).unwrap();
     assert_eq!(owner, contract_id);
+
+    let execution = factory
+        .view("get_execution")
+        .args_json(json!({"execution_id": 0}))
+        .await
+        .unwrap()
+        .json::<Option<ExecutionRecord>>()
+        .unwrap()
+        .unwrap();
+    assert_eq!(execution.receiver_id.as_str(), contract_id.as_str());
+    assert_eq!(execution.method_names, vec!["set_owner".to_string()]);
+    assert!(matches!(
+        execution.status,
+        ExecutionStatus::Succeeded { .. }
+    ));
+}
+
+#[tokio::test]
+async fn test_delegate_execution_to_failed_receiver() {
+    let (factory_owner, factory, contract_id) = create_factory().await;
+
+    let result = factory_owner
+        .call(factory.id(), "delegate_execution")
+        .deposit(NearToken::from_yoctonear(1))
+        .args_json(json!({
+            "receiver_id": &contract_id,
+            "actions": vec![FunctionCallArgs {
+                function_name: "unknown_method".to_string(),
+                arguments: vec![].into(),
+                amount: NearToken::from_near(0),
+                gas: Gas::from_tgas(5)
+            }]
+        }))
+        .max_gas()
+        .transact()
+        .await
+        .unwrap();
+    assert!(result.is_failure(), "{result:#?}");
+
+    let execution = factory
+        .view("get_execution")
+        .args_json(json!({"execution_id": 0}))
+        .await
+        .unwrap()
+        .json::<Option<ExecutionRecord>>()
+        .unwrap()
+        .unwrap();
+    assert_eq!(execution.status, ExecutionStatus::Failed);
+}
+
+#[tokio::test]
+async fn test_delegate_execution_plan() {
+    let (factory_owner, factory, contract_id) = create_factory().await;
+    let unknown_method = FunctionCallArgs {
+        function_name: "unknown_method".to_string(),
+        arguments: vec![].into(),
+        amount: NearToken::from_near(0),
+        gas: Gas::from_tgas(5),
+    };
+    let set_owner = FunctionCallArgs {
+        function_name: "set_owner".to_string(),
+        arguments: near_sdk::borsh::to_vec(&contract_id)
+            .map(Into::into)
+            .unwrap(),
+        amount: NearToken::from_near(0),
+        gas: Gas::from_tgas(5),
+    };
+
+    for (mode, statuses) in [
+        (
+            "sequential",
+            [ExecutionStepStatus::Failed, ExecutionStepStatus::Skipped],
+        ),
+        (
+            "parallel",
+            [ExecutionStepStatus::Failed, ExecutionStepStatus::Succeeded],
+        ),
+    ] {
+        let result = factory_owner
+            .call(factory.id(), "delegate_execution_plan")
+            .deposit(NearToken::from_yoctonear(1))
+            .args_json(json!({
+                "steps": [
+                    {"receiver_id": &contract_id, "actions": [&unknown_method]},
+                    {"receiver_id": &contract_id, "actions": [&set_owner]},
+                ],
+                "mode": mode
+            }))
+            .max_gas()
+            .transact()
+            .await
+            .unwrap();
+        assert!(result.is_success(), "{result:#?}");
+
+        let results: Vec<ExecutionStepResult> = result.json().unwrap();
+        assert_eq!(
+            results
+                .iter()
+                .map(|result| result.status)
+                .collect::<Vec<_>>(),
+            statuses
+        );
+
+        for result in results {
+            let execution = factory
+                .view("get_execution")
+                .args_json(json!({"execution_id": result.execution_id}))
+                .await
+                .unwrap()
+                .json::<Option<ExecutionRecord>>()
+                .unwrap()
+                .unwrap();
+            assert_eq!(
+                execution.status == ExecutionStatus::Skipped,
+                result.status == ExecutionStepStatus::Skipped
+            );
+        }
+    }
+
+    let bytes = factory_owner
+        .call(&contract_id, "get_owner")
+        .view()
+        .await
+        .unwrap()
+        .result;
+    let owner = AccountId::from_str(&String::from_utf8(bytes).unwrap()).unwrap();
+    assert_eq!(owner, contract_id);
 }
 
 #[near(serializers = [borsh])]
@@ -98,12 +229,89 @@ async fn test_delegate_pause_via_plugins() {
     assert!(result.is_success(), "{result:#?}");
 }
 
+#[tokio::test]
+async fn test_delegate_unpause_via_plugins() {
+    let (_, factory, _) = create_factory().await;
+
+    for (method, args) in [
+        (
+            "delegate_pause",
+            json!({
+                "receiver_id": &factory.id(),
+                "pause_method_name": "pa_pause_feature",
+                "pause_arguments": {"key": "ALL"}
+            }),
+        ),
+        (
+            "delegate_unpause",
+            json!({
+                "receiver_id": &factory.id(),
+                "unpause_method_name": "pa_unpause_feature",
+                "unpause_arguments": {"key": "ALL"}
+            }),
+        ),
+    ] {
+        let result = factory
+            .call(method)
+            .deposit(NearToken::from_yoctonear(1))
+            .args_json(args)
+            .max_gas()
+            .transact()
+            .await
+            .unwrap();
+        assert!(result.is_success(), "{result:#?}");
+    }
+
+    let is_paused: bool = factory
+        .view("pa_is_paused")
+        .args_json(json!({"key": "ALL"}))
+        .await
+        .unwrap()
+        .json()
+        .unwrap();
+    assert!(!is_paused);
+}
+
+#[tokio::test]
+async fn test_pause_all() {
+    let (factory_owner, factory, contract_id) = create_factory().await;
+
+    let result = factory_owner
+        .call(factory.id(), "pause_all")
+        .deposit(NearToken::from_yoctonear(1))
+        .args_json(json!({}))
+        .max_gas()
+        .transact()
+        .await
+        .unwrap();
+    assert!(result.is_success(), "{result:#?}");
+    let fleet_pause_id: u64 = result.json().unwrap();
+
+    let fleet_pause: FleetPause = factory_owner
+        .view(factory.id(), "get_fleet_pause")
+        .args_json(json!({"fleet_pause_id": fleet_pause_id}))
+        .await
+        .unwrap()
+        .json()
+        .unwrap();
+    assert!(fleet_pause.is_completed());
+
+    let status: BTreeMap<AccountId, Option<PauseStatus>> = factory_owner
+        .view(factory.id(), "get_fleet_pause_status")
+        .args_json(json!({}))
+        .await
+        .unwrap()
+        .json()
+        .unwrap();
+    assert_eq!(status[&contract_id], Some(PauseStatus::Paused));
+}
+
 async fn create_factory() -> (Account, Contract, AccountId) {
     let (factory_owner, factory, _) = utils::crate_factory().await.unwrap();
 
     let result = factory_owner
         .call(factory.id(), "add_release_info")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_millinear(100))
         .args_json(json!({
             "hash": HASH_3_6_4,
             "version": "3.6.4",
@@ -117,7 +325,7 @@ async fn create_factory() -> (Account, Contract, AccountId) {
 
     let result = factory_owner
         .call(factory.id(), "add_release_blob")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_near(15))
         .args(BLOB_3_6_4.to_vec())
         .max_gas()
         .transact()
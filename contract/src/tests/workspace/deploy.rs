@@ -5,7 +5,7 @@ use std::collections::BTreeMap;
 
 use super::utils;
 use crate::tests::{BLOB_3_6_4, BLOB_3_7_0, HASH_3_6_4, HASH_3_7_0, MIGRATION_GAS};
-use crate::types::DeploymentInfo;
+use crate::types::{AccessKeyInfo, DeploymentInfo, KeyPermission, KeyPolicy};
 
 #[tokio::test]
 async fn test_deploy_contract() {
@@ -20,7 +20,7 @@ async fn test_deploy_contract() {
 
     let result = factory_owner
         .call(factory.id(), "add_release_info")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_millinear(100))
         .args_json(json!({
             "hash": HASH_3_6_4,
             "version": "3.6.4",
@@ -34,7 +34,7 @@ async fn test_deploy_contract() {
 
     let result = factory_owner
         .call(factory.id(), "add_release_blob")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_near(15))
         .args(BLOB_3_6_4.to_vec())
         .max_gas()
         .transact()
@@ -91,7 +91,7 @@ async fn test_deploy_more_than_one_contract() {
 
     let result = factory_owner
         .call(factory.id(), "add_release_info")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_millinear(100))
         .args_json(json!({
             "hash": HASH_3_6_4,
             "version": "3.6.4",
@@ -105,7 +105,7 @@ async fn test_deploy_more_than_one_contract() {
 
     let result = factory_owner
         .call(factory.id(), "add_release_blob")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_near(15))
         .args(BLOB_3_6_4.to_vec())
         .max_gas()
         .transact()
@@ -143,7 +143,7 @@ async fn test_deploy_more_than_one_contract() {
 
     let result = factory_owner
         .call(factory.id(), "add_release_info")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_millinear(100))
         .args_json(json!({
             "hash": HASH_3_7_0,
             "version": "3.7.0",
@@ -157,7 +157,7 @@ async fn test_deploy_more_than_one_contract() {
 
     let result = factory_owner
         .call(factory.id(), "add_release_blob")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_near(15))
         .args(BLOB_3_7_0.to_vec())
         .max_gas()
         .transact()
@@ -201,6 +201,16 @@ async fn test_deploy_more_than_one_contract() {
         .json()
         .unwrap();
     assert_eq!(deployments.len(), 2);
+    // The full access key of the signer is added while the default keys aren't set.
+    let signer_keys = vec![AccessKeyInfo {
+        public_key: factory_owner
+            .secret_key()
+            .public_key()
+            .to_string()
+            .parse()
+            .unwrap(),
+        permission: KeyPermission::FullAccess,
+    }];
     assert_eq!(
         deployments,
         BTreeMap::from_iter([
@@ -212,6 +222,11 @@ async fn test_deploy_more_than_one_contract() {
                     deployment_time: deploy_time_1,
                     upgrade_times: [(deploy_time_1, "3.6.4".parse().unwrap())].into(),
                     init_args: near_sdk::serde_json::to_string(&init_args_1).unwrap(),
+                    last_error: None,
+                    channel: None,
+                    family: None,
+                    key_policy: Some(KeyPolicy::Signer),
+                    access_keys: signer_keys.clone(),
                 }
             ),
             (
@@ -222,6 +237,11 @@ async fn test_deploy_more_than_one_contract() {
                     deployment_time: deploy_time_2,
                     upgrade_times: [(deploy_time_2, "3.7.0".parse().unwrap())].into(),
                     init_args: near_sdk::serde_json::to_string(&init_args_2).unwrap(),
+                    last_error: None,
+                    channel: None,
+                    family: None,
+                    key_policy: Some(KeyPolicy::Signer),
+                    access_keys: signer_keys,
                 }
             )
         ])
@@ -273,6 +293,11 @@ async fn test_add_deployment_info_for_existed_contract() {
             deployment_time: 0,
             upgrade_times: BTreeMap::default(),
             init_args: String::default(),
+            last_error: None,
+            channel: None,
+            family: None,
+            key_policy: None,
+            access_keys: vec![],
         };
 
         let result = factory_owner
@@ -290,7 +315,7 @@ async fn test_add_deployment_info_for_existed_contract() {
 
     let result = factory_owner
         .call(factory.id(), "add_release_info")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_millinear(100))
         .args_json(json!({
             "hash": HASH_3_7_0,
             "version": "3.7.0",
@@ -304,7 +329,7 @@ async fn test_add_deployment_info_for_existed_contract() {
 
     let result = factory_owner
         .call(factory.id(), "add_release_blob")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_near(15))
         .args(BLOB_3_7_0.to_vec())
         .max_gas()
         .transact()
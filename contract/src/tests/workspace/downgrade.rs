@@ -13,7 +13,7 @@ async fn test_downgrade_contract() {
 
     let result = factory_owner
         .call(factory.id(), "add_release_info")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_millinear(100))
         .args_json(json!({
             "hash": HASH_3_6_4,
             "version": "3.6.4",
@@ -27,7 +27,7 @@ async fn test_downgrade_contract() {
 
     let result = factory_owner
         .call(factory.id(), "add_release_blob")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_near(15))
         .args(BLOB_3_6_4.to_vec())
         .max_gas()
         .transact()
@@ -37,7 +37,7 @@ async fn test_downgrade_contract() {
 
     let result = factory_owner
         .call(factory.id(), "add_release_info")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_millinear(100))
         .args_json(json!({
             "hash": HASH_3_7_0,
             "version": "3.7.0",
@@ -51,7 +51,7 @@ async fn test_downgrade_contract() {
 
     let result = factory_owner
         .call(factory.id(), "add_release_blob")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_near(15))
         .args(BLOB_3_7_0.to_vec())
         .max_gas()
         .transact()
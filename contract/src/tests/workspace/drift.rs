@@ -0,0 +1,114 @@
+use near_sdk::serde_json::json;
+use near_workspaces::network::Sandbox;
+use near_workspaces::types::NearToken;
+use near_workspaces::{Account, AccountId, Worker};
+use std::collections::BTreeMap;
+
+use super::utils;
+use crate::tests::{BLOB_3_6_4, BLOB_3_7_0, HASH_3_6_4, HASH_3_7_0};
+use crate::types::CodeDrift;
+
+#[tokio::test]
+async fn test_report_code_hash_drift() {
+    let (factory_owner, factory, worker) = utils::crate_factory().await.unwrap();
+
+    let result = factory_owner
+        .call(factory.id(), "add_release_info")
+        .deposit(NearToken::from_millinear(100))
+        .args_json(json!({
+            "hash": HASH_3_6_4,
+            "version": "3.6.4",
+            "is_latest": true,
+            "downgrade_hash": null
+        }))
+        .transact()
+        .await
+        .unwrap();
+    assert!(result.is_success(), "{result:#?}");
+
+    let result = factory_owner
+        .call(factory.id(), "add_release_blob")
+        .deposit(NearToken::from_near(15))
+        .args(BLOB_3_6_4.to_vec())
+        .max_gas()
+        .transact()
+        .await
+        .unwrap();
+    assert!(result.is_success(), "{result:#?}");
+
+    let new_contract_id: AccountId = "aurora.factory-owner.test.near".parse().unwrap();
+    let result = factory_owner
+        .call(factory.id(), "deploy")
+        .args_json(json!({
+            "new_contract_id": new_contract_id.clone(),
+            "init_method": "new",
+            "init_args": json!({
+                "chain_id": 1_313_161_559,
+                "owner_id": factory_owner.id(),
+                "upgrade_delay_blocks": 0,
+                "key_manager": factory_owner.id(),
+                "initial_hashchain": null
+            })
+        }))
+        .max_gas()
+        .deposit(NearToken::from_near(25))
+        .transact()
+        .await
+        .unwrap();
+    assert!(result.is_success(), "{result:#?}");
+
+    assert!(
+        report_code_hash(&worker, &factory_owner, factory.id(), &new_contract_id)
+            .await
+            .is_empty()
+    );
+
+    // Redeploy the contract by the full access key added in `deploy`.
+    let contract_account = Account::from_secret_key(
+        new_contract_id.clone(),
+        factory_owner.secret_key().clone(),
+        &worker,
+    );
+    let result = contract_account.deploy(BLOB_3_7_0).await.unwrap();
+    assert!(result.is_success(), "{result:#?}");
+
+    assert_eq!(
+        report_code_hash(&worker, &factory_owner, factory.id(), &new_contract_id).await,
+        vec![new_contract_id.clone()]
+    );
+
+    let drifts: BTreeMap<AccountId, CodeDrift> = factory_owner
+        .view(factory.id(), "get_drifted_deployments")
+        .args_json(json!({}))
+        .await
+        .unwrap()
+        .json()
+        .unwrap();
+    let drift = &drifts[&new_contract_id];
+    assert_eq!(drift.expected_hash, HASH_3_6_4);
+    assert_eq!(drift.actual_hash, HASH_3_7_0);
+}
+
+async fn report_code_hash(
+    worker: &Worker<Sandbox>,
+    factory_owner: &Account,
+    factory_id: &AccountId,
+    contract_id: &AccountId,
+) -> Vec<AccountId> {
+    let code_hash = worker
+        .view_account(contract_id)
+        .await
+        .unwrap()
+        .code_hash
+        .to_string();
+    let result = factory_owner
+        .call(factory_id, "report_code_hashes")
+        .args_json(json!({"code_hashes": {contract_id.as_str(): code_hash}}))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await
+        .unwrap();
+    assert!(result.is_success(), "{result:#?}");
+
+    result.json().unwrap()
+}
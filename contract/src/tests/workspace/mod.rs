@@ -1,6 +1,7 @@
 mod delegate;
 mod deploy;
 mod downgrade;
+mod drift;
 mod release;
 mod upgrade;
 mod utils;
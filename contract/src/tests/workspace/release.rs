@@ -1,6 +1,8 @@
+use near_sdk::json_types::Base64VecU8;
 use near_sdk::{serde_json::json, NearToken};
 
 use super::utils;
+use crate::tests::{BLOB_3_6_4, HASH_3_6_4};
 use crate::types::ReleaseInfo;
 
 #[tokio::test]
@@ -9,7 +11,7 @@ async fn test_add_new_release() {
 
     let result = factory_owner
         .call(factory.id(), "add_release_info")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_millinear(100))
         .args_json(json!({
             "hash": "f5c22e35d04167e37913e7963ce033b1f3d17a924a4e6fe5fc95af1224051921",
             "version": "1.0.1",
@@ -23,7 +25,7 @@ async fn test_add_new_release() {
 
     let result = factory_owner
         .call(factory.id(), "add_release_info")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_millinear(100))
         .args_json(json!({
             "hash": "2661920f2409dd6c8adeb0c44972959f232b6429afa913845d0fd95e7e768234",
             "version": "1.0.0",
@@ -45,3 +47,97 @@ async fn test_add_new_release() {
 
     assert_eq!(releases.len(), 1);
 }
+
+#[tokio::test]
+async fn test_add_release_blob_by_chunks() {
+    let (factory_owner, factory, _) = utils::crate_factory().await.unwrap();
+    let chunk_size = 400_000;
+    let chunks_count = BLOB_3_6_4.len().div_ceil(chunk_size);
+
+    let result = factory_owner
+        .call(factory.id(), "add_release_info")
+        .deposit(NearToken::from_millinear(100))
+        .args_json(json!({
+            "hash": HASH_3_6_4,
+            "version": "3.6.4",
+            "is_latest": true,
+            "downgrade_hash": null
+        }))
+        .transact()
+        .await
+        .unwrap();
+    assert!(result.is_success(), "{result:#?}");
+
+    let result = factory_owner
+        .call(factory.id(), "begin_release_blob_upload")
+        .deposit(NearToken::from_millinear(100))
+        .args_json(json!({
+            "hash": HASH_3_6_4,
+            "total_size": BLOB_3_6_4.len(),
+            "chunks_count": chunks_count
+        }))
+        .transact()
+        .await
+        .unwrap();
+    assert!(result.is_success(), "{result:#?}");
+
+    // Upload chunks in the reverse order to check that the order doesn't matter.
+    for (index, chunk) in BLOB_3_6_4.chunks(chunk_size).enumerate().skip(1).rev() {
+        let result = factory_owner
+            .call(factory.id(), "add_release_blob_chunk")
+            .deposit(NearToken::from_near(5))
+            .args_json(json!({
+                "hash": HASH_3_6_4,
+                "index": index,
+                "data": Base64VecU8::from(chunk.to_vec())
+            }))
+            .max_gas()
+            .transact()
+            .await
+            .unwrap();
+        assert!(result.is_success(), "{result:#?}");
+    }
+
+    let missing_chunks: Vec<u32> = factory_owner
+        .view(factory.id(), "get_missing_blob_chunks")
+        .args_json(json!({"hash": HASH_3_6_4}))
+        .await
+        .unwrap()
+        .json()
+        .unwrap();
+    assert_eq!(missing_chunks, vec![0]);
+
+    let result = factory_owner
+        .call(factory.id(), "add_release_blob_chunk")
+        .deposit(NearToken::from_near(5))
+        .args_json(json!({
+            "hash": HASH_3_6_4,
+            "index": 0,
+            "data": Base64VecU8::from(BLOB_3_6_4[..chunk_size].to_vec())
+        }))
+        .max_gas()
+        .transact()
+        .await
+        .unwrap();
+    assert!(result.is_success(), "{result:#?}");
+
+    let result = factory_owner
+        .call(factory.id(), "finalize_release_blob_upload")
+        .deposit(NearToken::from_near(1))
+        .args_json(json!({"hash": HASH_3_6_4}))
+        .max_gas()
+        .transact()
+        .await
+        .unwrap();
+    assert!(result.is_success(), "{result:#?}");
+
+    let releases: Vec<ReleaseInfo> = factory_owner
+        .call(factory.id(), "get_releases")
+        .view()
+        .await
+        .unwrap()
+        .json()
+        .unwrap();
+    assert_eq!(releases.len(), 1);
+    assert!(releases[0].is_blob_exist);
+}
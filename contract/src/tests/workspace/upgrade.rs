@@ -5,14 +5,14 @@ use std::collections::BTreeMap;
 
 use super::utils;
 use crate::tests::{BLOB_3_6_4, BLOB_3_7_0, HASH_3_6_4, HASH_3_7_0, MIGRATION_GAS};
-use crate::types::DeploymentInfo;
+use crate::types::{BatchUpgrade, DeploymentAction, DeploymentInfo, UpgradeStatus};
 
 #[tokio::test]
 async fn test_upgrade_contract() {
     let (factory_owner, factory, _) = utils::crate_factory().await.unwrap();
     let result = factory_owner
         .call(factory.id(), "add_release_info")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_millinear(100))
         .args_json(json!({
             "hash": HASH_3_6_4,
             "version": "3.6.4",
@@ -26,7 +26,7 @@ async fn test_upgrade_contract() {
 
     let result = factory_owner
         .call(factory.id(), "add_release_blob")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_near(15))
         .args(BLOB_3_6_4.to_vec())
         .max_gas()
         .transact()
@@ -57,7 +57,7 @@ async fn test_upgrade_contract() {
 
     let result = factory_owner
         .call(factory.id(), "add_release_info")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_millinear(100))
         .args_json(json!({
             "hash": HASH_3_7_0,
             "version": "3.7.0",
@@ -71,7 +71,7 @@ async fn test_upgrade_contract() {
 
     let result = factory_owner
         .call(factory.id(), "add_release_blob")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_near(15))
         .args(BLOB_3_7_0.to_vec())
         .max_gas()
         .transact()
@@ -99,7 +99,7 @@ async fn test_upgrade_contract_to_previous_version() {
     let (factory_owner, factory, _) = utils::crate_factory().await.unwrap();
     let result = factory_owner
         .call(factory.id(), "add_release_info")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_millinear(100))
         .args_json(json!({
             "hash": HASH_3_7_0,
             "version": "3.7.0",
@@ -113,7 +113,7 @@ async fn test_upgrade_contract_to_previous_version() {
 
     let result = factory_owner
         .call(factory.id(), "add_release_blob")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_near(15))
         .args(BLOB_3_7_0.to_vec())
         .max_gas()
         .transact()
@@ -144,7 +144,7 @@ async fn test_upgrade_contract_to_previous_version() {
 
     let result = factory_owner
         .call(factory.id(), "add_release_info")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_millinear(100))
         .args_json(json!({
             "hash": HASH_3_6_4,
             "version": "3.6.4",
@@ -158,7 +158,7 @@ async fn test_upgrade_contract_to_previous_version() {
 
     let result = factory_owner
         .call(factory.id(), "add_release_blob")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_near(15))
         .args(BLOB_3_6_4.to_vec())
         .max_gas()
         .transact()
@@ -187,7 +187,7 @@ async fn test_unrestricted_upgrade_contract() {
     let (factory_owner, factory, _) = utils::crate_factory().await.unwrap();
     let result = factory_owner
         .call(factory.id(), "add_release_info")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_millinear(100))
         .args_json(json!({
             "hash": HASH_3_7_0,
             "version": "3.7.0",
@@ -201,7 +201,7 @@ async fn test_unrestricted_upgrade_contract() {
 
     let result = factory_owner
         .call(factory.id(), "add_release_blob")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_near(15))
         .args(BLOB_3_7_0.to_vec())
         .max_gas()
         .transact()
@@ -232,7 +232,7 @@ async fn test_unrestricted_upgrade_contract() {
 
     let result = factory_owner
         .call(factory.id(), "add_release_info")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_millinear(100))
         .args_json(json!({
             "hash": HASH_3_6_4,
             "version": "3.6.4",
@@ -246,7 +246,7 @@ async fn test_unrestricted_upgrade_contract() {
 
     let result = factory_owner
         .call(factory.id(), "add_release_blob")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_near(15))
         .args(BLOB_3_6_4.to_vec())
         .max_gas()
         .transact()
@@ -274,7 +274,7 @@ async fn test_upgrade_contract_with_small_gas_for_migration() {
     let (factory_owner, factory, _) = utils::crate_factory().await.unwrap();
     let result = factory_owner
         .call(factory.id(), "add_release_info")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_millinear(100))
         .args_json(json!({
             "hash": HASH_3_6_4,
             "version": "3.6.4",
@@ -288,7 +288,7 @@ async fn test_upgrade_contract_with_small_gas_for_migration() {
 
     let result = factory_owner
         .call(factory.id(), "add_release_blob")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_near(15))
         .args(BLOB_3_6_4.to_vec())
         .max_gas()
         .transact()
@@ -326,7 +326,7 @@ async fn test_upgrade_contract_with_small_gas_for_migration() {
 
     let result = factory_owner
         .call(factory.id(), "add_release_info")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_millinear(100))
         .args_json(json!({
             "hash": HASH_3_7_0,
             "version": "3.7.0",
@@ -340,7 +340,7 @@ async fn test_upgrade_contract_with_small_gas_for_migration() {
 
     let result = factory_owner
         .call(factory.id(), "add_release_blob")
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(NearToken::from_near(15))
         .args(BLOB_3_7_0.to_vec())
         .max_gas()
         .transact()
@@ -363,16 +363,161 @@ async fn test_upgrade_contract_with_small_gas_for_migration() {
     assert!(result.is_success()); // But the promise for the state migration will be failed.
                                   // So, the upgrade won't be successful.
 
-    // Check that the deployment into hasn't been changed.
-    let result: BTreeMap<AccountId, DeploymentInfo> = factory_owner
+    // Check that the deployment info hasn't been changed except the recorded error.
+    let mut result: BTreeMap<AccountId, DeploymentInfo> = factory_owner
         .view(factory.id(), "get_deployments")
         .await
         .unwrap()
         .json()
         .unwrap();
+    let last_error = result
+        .get_mut(&new_contract_id)
+        .and_then(|info| info.last_error.take())
+        .unwrap();
+    assert_eq!(last_error.action, DeploymentAction::Upgrade);
+    assert_eq!(last_error.hash, HASH_3_7_0);
+    assert_eq!(last_error.version, "3.7.0".parse().unwrap());
     assert_eq!(deployments_info, result);
 
     let result = factory_owner.view(&new_contract_id, "get_version").await;
     let version = String::from_utf8(result.unwrap().result).unwrap();
     assert_eq!(version.trim_end(), "3.6.4");
 }
+
+#[tokio::test]
+#[allow(clippy::too_many_lines)]
+async fn test_batch_upgrade_contracts() {
+    let (factory_owner, factory, _) = utils::crate_factory().await.unwrap();
+    let result = factory_owner
+        .call(factory.id(), "add_release_info")
+        .deposit(NearToken::from_millinear(100))
+        .args_json(json!({
+            "hash": HASH_3_6_4,
+            "version": "3.6.4",
+            "is_latest": true,
+            "downgrade_hash": null
+        }))
+        .transact()
+        .await
+        .unwrap();
+    assert!(result.is_success(), "{result:#?}");
+
+    let result = factory_owner
+        .call(factory.id(), "add_release_blob")
+        .deposit(NearToken::from_near(15))
+        .args(BLOB_3_6_4.to_vec())
+        .max_gas()
+        .transact()
+        .await
+        .unwrap();
+    assert!(result.is_success(), "{result:#?}");
+
+    let contract_ids: Vec<AccountId> = vec![
+        "aurora-1.factory-owner.test.near".parse().unwrap(),
+        "aurora-2.factory-owner.test.near".parse().unwrap(),
+    ];
+
+    for contract_id in &contract_ids {
+        let result = factory_owner
+            .call(factory.id(), "deploy")
+            .args_json(json!({
+                "new_contract_id": contract_id,
+                "init_method": "new",
+                "init_args": json!({
+                    "chain_id": 1_313_161_559,
+                    "owner_id": factory_owner.id(),
+                    "upgrade_delay_blocks": 0,
+                    "key_manager": factory_owner.id(),
+                    "initial_hashchain": null
+                })
+            }))
+            .deposit(NearToken::from_near(25))
+            .max_gas()
+            .transact()
+            .await
+            .unwrap();
+        assert!(result.is_success(), "{result:#?}");
+    }
+
+    let result = factory_owner
+        .call(factory.id(), "add_release_info")
+        .deposit(NearToken::from_millinear(100))
+        .args_json(json!({
+            "hash": HASH_3_7_0,
+            "version": "3.7.0",
+            "is_latest": true,
+            "downgrade_hash": null
+        }))
+        .transact()
+        .await
+        .unwrap();
+    assert!(result.is_success(), "{result:#?}");
+
+    let result = factory_owner
+        .call(factory.id(), "add_release_blob")
+        .deposit(NearToken::from_near(15))
+        .args(BLOB_3_7_0.to_vec())
+        .max_gas()
+        .transact()
+        .await
+        .unwrap();
+    assert!(result.is_success(), "{result:#?}");
+
+    let result = factory_owner
+        .call(factory.id(), "batch_upgrade")
+        .deposit(NearToken::from_yoctonear(1))
+        .args_json(json!({
+            "filter": {
+                "max_version": "3.7.0"
+            },
+            "state_migration_gas": MIGRATION_GAS
+        }))
+        .max_gas()
+        .transact()
+        .await
+        .unwrap();
+    assert!(result.is_success(), "{result:#?}");
+    let batch_id: u64 = result.json().unwrap();
+
+    // Start the upgrades which haven't fit into the gas of the first transaction.
+    loop {
+        let batch: BatchUpgrade = factory_owner
+            .view(factory.id(), "get_batch_upgrade")
+            .args_json(json!({"batch_id": batch_id}))
+            .await
+            .unwrap()
+            .json()
+            .unwrap();
+
+        if batch.pending_targets().is_empty() {
+            break;
+        }
+
+        let result = factory_owner
+            .call(factory.id(), "resume_batch_upgrade")
+            .deposit(NearToken::from_yoctonear(1))
+            .args_json(json!({"batch_id": batch_id}))
+            .max_gas()
+            .transact()
+            .await
+            .unwrap();
+        assert!(result.is_success(), "{result:#?}");
+    }
+
+    let batch: BatchUpgrade = factory_owner
+        .view(factory.id(), "get_batch_upgrade")
+        .args_json(json!({"batch_id": batch_id}))
+        .await
+        .unwrap()
+        .json()
+        .unwrap();
+    assert!(batch.is_completed());
+
+    for contract_id in &contract_ids {
+        assert_eq!(batch.targets[contract_id], UpgradeStatus::Succeeded);
+
+        let result = factory_owner.view(contract_id, "get_version").await;
+        let version = String::from_utf8(result.unwrap().result).unwrap();
+        assert_eq!(version.trim_end(), "3.7.0");
+    }
+}
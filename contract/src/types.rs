@@ -1,13 +1,15 @@
 use near_sdk::base64::Engine;
 use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
 use near_sdk::json_types::Base64VecU8;
-use near_sdk::serde::{Deserialize, Serialize};
-use near_sdk::{base64, near, Gas, NearToken};
-use std::collections::BTreeMap;
+use near_sdk::serde::Serialize;
+use near_sdk::{base64, near, AccountId, Gas, NearToken, PublicKey};
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::{Display, Formatter};
 use std::io::{Read, Write};
 use std::str::FromStr;
 
+use crate::Role;
+
 /// If the length of arguments bytes is more then `MAX_ARGS_LENGTH` than decrease length of
 /// arguments in the `LogFunctionCallArgs` to prevent the error:
 /// `The length of a log message exceeds the limit 16384`.
@@ -28,6 +30,76 @@ pub struct ReleaseInfo {
     pub downgrade_hash: Option<String>,
     /// Description of the release.
     pub description: Option<String>,
+    /// Family of the contract which the release belongs to. The default family is used if it
+    /// isn't set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family: Option<String>,
+}
+
+/// Entities which reference the release by its hash.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json])]
+pub struct ReleaseReferences {
+    /// Account ids of the contracts deployed with the release.
+    pub deployments: Vec<AccountId>,
+    /// Flag which displays whether the release is the latest one.
+    pub is_latest: bool,
+    /// Hashes of the releases which use the release as the downgrade hash.
+    pub downgrade_releases: Vec<String>,
+    /// Account ids of the contracts with the scheduled upgrades to the release.
+    pub scheduled_upgrades: Vec<AccountId>,
+    /// Names of the channels which the release is the latest in.
+    pub channels: Vec<String>,
+}
+
+impl ReleaseReferences {
+    /// Checks whether the release isn't referenced by anything.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.deployments.is_empty()
+            && !self.is_latest
+            && self.downgrade_releases.is_empty()
+            && self.scheduled_upgrades.is_empty()
+            && self.channels.is_empty()
+    }
+}
+
+impl Display for ReleaseReferences {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let join_ids = |ids: &[AccountId]| {
+            ids.iter()
+                .map(AccountId::as_str)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let mut references = vec![];
+
+        if !self.deployments.is_empty() {
+            references.push(format!("deployments: {}", join_ids(&self.deployments)));
+        }
+        if self.is_latest {
+            references.push("the latest release".to_string());
+        }
+        if !self.downgrade_releases.is_empty() {
+            references.push(format!(
+                "downgrade hash of releases: {}",
+                self.downgrade_releases.join(", ")
+            ));
+        }
+        if !self.scheduled_upgrades.is_empty() {
+            references.push(format!(
+                "scheduled upgrades: {}",
+                join_ids(&self.scheduled_upgrades)
+            ));
+        }
+
+        if !self.channels.is_empty() {
+            references.push(format!("channels: {}", self.channels.join(", ")));
+        }
+
+        f.write_str(&references.join("; "))
+    }
 }
 
 /// Deployment information of the deployed contract.
@@ -45,9 +117,42 @@ pub struct DeploymentInfo {
     pub upgrade_times: BTreeMap<u64, Version>,
     /// Initial arguments used while deploying the contact.
     pub init_args: String,
+    /// The last failed upgrade or downgrade of the contract.
+    #[serde(default)]
+    pub last_error: Option<DeploymentError>,
+    /// Release channel which the contract is subscribed to. The latest release is used if it
+    /// isn't set.
+    #[serde(default)]
+    pub channel: Option<String>,
+    /// Family of the deployed contract. Only releases of the same family could be deployed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family: Option<String>,
+    /// Policy of the access keys added to the account of the contract while deploying.
+    #[serde(default)]
+    pub key_policy: Option<KeyPolicy>,
+    /// Access keys added to the account of the contract by the key policy.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub access_keys: Vec<AccessKeyInfo>,
 }
 
 impl DeploymentInfo {
+    /// Checks that the release belongs to the family of the deployed contract.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error if the families of the release and the deployment don't match.
+    pub fn check_family(&self, release_info: &ReleaseInfo) -> Result<(), String> {
+        if self.family == release_info.family {
+            Ok(())
+        } else {
+            Err(format!(
+                "release family: {} doesn't match the deployment family: {}",
+                family_name(release_info.family.as_ref()),
+                family_name(self.family.as_ref())
+            ))
+        }
+    }
+
     pub fn update(&mut self, hash: String, version: Version) {
         self.hash = hash;
         self.version = version.clone();
@@ -56,6 +161,438 @@ impl DeploymentInfo {
     }
 }
 
+/// Action made by the controller on the deployed contract.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[near(serializers = [json, borsh])]
+#[serde(rename_all = "snake_case")]
+pub enum DeploymentAction {
+    /// Deployment of the new contract.
+    Deploy,
+    /// Upgrade of the contract.
+    Upgrade,
+    /// Downgrade of the contract.
+    Downgrade,
+}
+
+/// Information about the failed action on the deployed contract.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json, borsh])]
+pub struct DeploymentError {
+    /// The failed action.
+    pub action: DeploymentAction,
+    /// `sha256` hash of the WASM contract which has been tried to deploy.
+    pub hash: String,
+    /// Version of the contract which has been tried to deploy.
+    pub version: Version,
+    /// Time of the failure.
+    pub timestamp: u64,
+}
+
+/// Returns the name of the family for the messages.
+#[must_use]
+pub fn family_name(family: Option<&String>) -> &str {
+    family.map_or("default", String::as_str)
+}
+
+/// Filter of the contract deployments.
+#[derive(Debug, Default, Clone)]
+#[near(serializers = [json])]
+pub struct DeploymentFilter {
+    /// Minimal version of the deployed contract (inclusive).
+    pub min_version: Option<Version>,
+    /// Maximal version of the deployed contract (exclusive).
+    pub max_version: Option<Version>,
+    /// `sha256` hash of the deployed WASM contract.
+    pub hash: Option<String>,
+    /// Minimal time of the contract deployment (inclusive).
+    pub deployed_after: Option<u64>,
+    /// Maximal time of the contract deployment (exclusive).
+    pub deployed_before: Option<u64>,
+    /// Family of the deployed contract.
+    #[serde(default)]
+    pub family: Option<String>,
+}
+
+impl DeploymentFilter {
+    /// Checks whether the deployment info matches the filter.
+    #[must_use]
+    pub fn matches(&self, deployment_info: &DeploymentInfo) -> bool {
+        self.min_version
+            .as_ref()
+            .is_none_or(|version| &deployment_info.version >= version)
+            && self
+                .max_version
+                .as_ref()
+                .is_none_or(|version| &deployment_info.version < version)
+            && self
+                .hash
+                .as_ref()
+                .is_none_or(|hash| &deployment_info.hash == hash)
+            && self
+                .deployed_after
+                .is_none_or(|time| deployment_info.deployment_time >= time)
+            && self
+                .deployed_before
+                .is_none_or(|time| deployment_info.deployment_time < time)
+            && self
+                .family
+                .as_ref()
+                .is_none_or(|family| deployment_info.family.as_ref() == Some(family))
+    }
+}
+
+/// Filter of the releases.
+#[derive(Debug, Default, Clone)]
+#[near(serializers = [json])]
+pub struct ReleaseFilter {
+    /// Minimal version of the release (inclusive).
+    pub min_version: Option<Version>,
+    /// Maximal version of the release (exclusive).
+    pub max_version: Option<Version>,
+    /// Flag which displays whether WASM data was added or not.
+    pub is_blob_exist: Option<bool>,
+}
+
+impl ReleaseFilter {
+    /// Checks whether the release info matches the filter.
+    #[must_use]
+    pub fn matches(&self, release_info: &ReleaseInfo) -> bool {
+        self.min_version
+            .as_ref()
+            .is_none_or(|version| &release_info.version >= version)
+            && self
+                .max_version
+                .as_ref()
+                .is_none_or(|version| &release_info.version < version)
+            && self
+                .is_blob_exist
+                .is_none_or(|is_blob_exist| release_info.is_blob_exist == is_blob_exist)
+    }
+}
+
+/// Status of the contract upgrade in the batch.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json, borsh])]
+#[serde(rename_all = "snake_case")]
+pub enum UpgradeStatus {
+    /// The upgrade hasn't been started yet.
+    Pending,
+    /// The upgrade has been started and waits for the result.
+    InProgress,
+    /// The contract has been upgraded successfully.
+    Succeeded,
+    /// The upgrade has failed with the reason.
+    Failed(String),
+}
+
+/// Upgrade of a batch of the deployed contracts.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json, borsh])]
+pub struct BatchUpgrade {
+    /// `sha256` hash of the WASM contract the contracts are upgraded to.
+    pub hash: String,
+    /// Amount of gas for the state migration.
+    pub state_migration_gas: Option<u64>,
+    /// Amount of gas attached to each upgrade instead of the default upgrade gas.
+    #[serde(default)]
+    pub upgrade_gas: Option<Gas>,
+    /// Time of the batch creation.
+    pub created_at: u64,
+    /// Statuses of the upgrades for each contract in the batch.
+    pub targets: BTreeMap<AccountId, UpgradeStatus>,
+    /// Flag which displays whether the contracts are downgraded, so the version check is skipped.
+    #[serde(default)]
+    pub is_downgrade: bool,
+}
+
+impl BatchUpgrade {
+    /// Returns account ids of the contracts which upgrades haven't been started yet.
+    #[must_use]
+    pub fn pending_targets(&self) -> Vec<AccountId> {
+        self.targets
+            .iter()
+            .filter(|(_, status)| matches!(status, UpgradeStatus::Pending))
+            .map(|(contract_id, _)| contract_id.clone())
+            .collect()
+    }
+
+    /// Checks whether all upgrades in the batch have been finished.
+    #[must_use]
+    pub fn is_completed(&self) -> bool {
+        self.targets
+            .values()
+            .all(|status| matches!(status, UpgradeStatus::Succeeded | UpgradeStatus::Failed(_)))
+    }
+
+    /// Checks whether all upgrades in the batch have been finished successfully.
+    #[must_use]
+    pub fn is_succeeded(&self) -> bool {
+        self.targets
+            .values()
+            .all(|status| matches!(status, UpgradeStatus::Succeeded))
+    }
+}
+
+/// Status of the contract pause in the fleet pause.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json, borsh])]
+#[serde(rename_all = "snake_case")]
+pub enum PauseStatus {
+    /// The pause method hasn't been called yet.
+    Pending,
+    /// The pause method has been called and waits for the result.
+    InProgress,
+    /// The contract has confirmed the pause by the status method.
+    Paused,
+    /// The pause method has succeeded, but the state of the contract hasn't been checked because
+    /// the status method isn't set.
+    Unverified,
+    /// The contract has been unpaused by `delegate_unpause` after the pause.
+    Unpaused,
+    /// The pause has failed with the reason.
+    Failed(String),
+}
+
+/// Pause of many deployed contracts at once.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json, borsh])]
+pub struct FleetPause {
+    /// Arguments of the pause methods in the JSON format.
+    pub pause_arguments: Option<String>,
+    /// Time of the fleet pause creation.
+    pub created_at: u64,
+    /// Statuses of the pauses for each contract.
+    pub targets: BTreeMap<AccountId, PauseStatus>,
+    /// Ids of the executions of the pause methods in the history of the delegated executions.
+    #[serde(default)]
+    pub execution_ids: BTreeMap<AccountId, u64>,
+}
+
+impl FleetPause {
+    /// Returns account ids of the contracts which pause methods haven't been called yet.
+    #[must_use]
+    pub fn pending_targets(&self) -> Vec<AccountId> {
+        self.targets
+            .iter()
+            .filter(|(_, status)| matches!(status, PauseStatus::Pending))
+            .map(|(contract_id, _)| contract_id.clone())
+            .collect()
+    }
+
+    /// Checks whether all pauses have been finished.
+    #[must_use]
+    pub fn is_completed(&self) -> bool {
+        self.targets
+            .values()
+            .all(|status| !matches!(status, PauseStatus::Pending | PauseStatus::InProgress))
+    }
+}
+
+/// Upgrade of the contract which could be executed after the announcement window.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json, borsh])]
+pub struct ScheduledUpgrade {
+    /// `sha256` hash of the WASM contract the contract is upgraded to.
+    pub hash: String,
+    /// Amount of gas for the state migration.
+    pub state_migration_gas: Option<u64>,
+    /// Flag which displays whether the version check is skipped.
+    pub is_unrestricted: bool,
+    /// Time of the upgrade announcement.
+    pub announced_at: u64,
+    /// The earliest time of the upgrade execution.
+    pub execute_after: u64,
+}
+
+/// Policy of the approvals of the proposals.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json, borsh])]
+pub struct ProposalPolicy {
+    /// Roles which members could create and approve proposals, e.g. `DAO`.
+    pub roles: Vec<Role>,
+    /// Number of the approvals required for executing the proposal.
+    pub threshold: u32,
+    /// Duration in nanoseconds after which the proposal expires.
+    pub duration: u64,
+}
+
+/// Sensitive action of the controller which is executed by the approved proposal.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json, borsh])]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalAction {
+    /// Attaches new full access key to the controller contract.
+    AttachFullAccessKey { public_key: PublicKey },
+    /// Upgrades a contract without checking version.
+    UnrestrictedUpgrade {
+        contract_id: AccountId,
+        hash: String,
+        state_migration_gas: Option<u64>,
+    },
+    /// Delegates an execution of actions to the receiver.
+    DelegateExecution {
+        receiver_id: AccountId,
+        actions: Vec<FunctionCallArgs>,
+    },
+    /// Changes or removes the proposal policy.
+    SetProposalPolicy { policy: Option<ProposalPolicy> },
+}
+
+/// Proposal of the sensitive action which waits for the approvals.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json, borsh])]
+pub struct Proposal {
+    /// Account which has created the proposal.
+    pub proposer: AccountId,
+    /// Action executed after reaching the threshold of approvals.
+    pub action: ProposalAction,
+    /// Accounts which have approved the proposal.
+    pub approvals: BTreeSet<AccountId>,
+    /// Deposit attached while creating the proposal.
+    pub deposit: NearToken,
+    /// Time of the proposal creation.
+    pub created_at: u64,
+    /// Time after which the proposal couldn't be approved.
+    pub expires_at: u64,
+    /// Flag which displays whether the proposal has been executed.
+    pub is_executed: bool,
+}
+
+impl Proposal {
+    /// Checks whether the proposal has been expired at the provided time.
+    #[must_use]
+    pub const fn is_expired(&self, timestamp: u64) -> bool {
+        !self.is_executed && timestamp >= self.expires_at
+    }
+}
+
+/// Selection of the deployed contracts for the rollout wave.
+#[derive(Debug, Clone)]
+#[near(serializers = [json])]
+#[serde(rename_all = "snake_case")]
+pub enum WaveSelection {
+    /// Explicit list of the contracts, e.g. canary contracts.
+    Contracts(Vec<AccountId>),
+    /// Percentage of all contracts in the rollout which are taken from the contracts
+    /// that haven't been selected by other waves.
+    Percentage(u8),
+}
+
+/// Wave of the staged rollout.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json, borsh])]
+pub struct RolloutWave {
+    /// Account ids of the contracts upgraded in the wave.
+    pub contract_ids: Vec<AccountId>,
+    /// Id of the batch upgrade which has been started for the wave.
+    pub batch_id: Option<u64>,
+    /// Hashes of the contracts deployed before the wave has been started.
+    pub previous_hashes: BTreeMap<AccountId, String>,
+    /// Ids of the batches which have rolled the wave back.
+    pub rollback_batch_ids: Vec<u64>,
+}
+
+/// Staged rollout of the release to the deployed contracts wave by wave.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json, borsh])]
+pub struct Rollout {
+    /// `sha256` hash of the WASM contract the contracts are upgraded to.
+    pub hash: String,
+    /// Amount of gas for the state migration.
+    pub state_migration_gas: Option<u64>,
+    /// Amount of gas attached to each upgrade instead of the default upgrade gas.
+    #[serde(default)]
+    pub upgrade_gas: Option<Gas>,
+    /// Time of the rollout creation.
+    pub created_at: u64,
+    /// Waves of the rollout in the order of the upgrading.
+    pub waves: Vec<RolloutWave>,
+}
+
+impl Rollout {
+    /// Returns index of the wave which should be started next.
+    #[must_use]
+    pub fn next_wave(&self) -> Option<usize> {
+        self.waves.iter().position(|wave| wave.batch_id.is_none())
+    }
+
+    /// Returns the last started wave.
+    #[must_use]
+    pub fn current_wave(&self) -> Option<&RolloutWave> {
+        self.waves.iter().rev().find(|wave| wave.batch_id.is_some())
+    }
+
+    /// Checks whether any wave of the rollout has been rolled back.
+    #[must_use]
+    pub fn is_rolled_back(&self) -> bool {
+        self.waves
+            .iter()
+            .any(|wave| !wave.rollback_batch_ids.is_empty())
+    }
+}
+
+/// State of the chunked upload of the release blob.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json, borsh])]
+pub struct BlobUpload {
+    /// Account which has started the upload.
+    pub uploader: AccountId,
+    /// Expected size of the blob in bytes.
+    pub total_size: u64,
+    /// Number of chunks the blob is split into.
+    pub chunks_count: u32,
+    /// Indexes of the chunks which have been already uploaded.
+    pub uploaded_chunks: BTreeSet<u32>,
+    /// Size of the uploaded chunks in bytes.
+    pub uploaded_size: u64,
+    /// Deposit paid for the storage staking of the uploaded chunks.
+    pub deposit: NearToken,
+}
+
+impl BlobUpload {
+    /// Returns indexes of the chunks which haven't been uploaded yet.
+    #[must_use]
+    pub fn missing_chunks(&self) -> Vec<u32> {
+        (0..self.chunks_count)
+            .filter(|index| !self.uploaded_chunks.contains(index))
+            .collect()
+    }
+}
+
+/// Storage staking paid by the account.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json, borsh])]
+pub struct StorageStake {
+    /// Account which paid for the storage.
+    pub account_id: AccountId,
+    /// Amount of the staked tokens.
+    pub amount: NearToken,
+}
+
+/// Storage stakes paid for the release info and its blob.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json, borsh])]
+pub struct ReleaseStorageStake {
+    /// Stake paid for the release info.
+    pub release_info: Option<StorageStake>,
+    /// Stake paid for the blob.
+    pub blob: Option<StorageStake>,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, PartialOrd)]
 #[near(serializers = [json])]
 pub struct Version(semver::Version);
@@ -96,7 +633,9 @@ impl Display for Version {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json, borsh])]
 pub struct FunctionCallArgs {
     pub function_name: String,
     pub arguments: Base64VecU8,
@@ -124,15 +663,496 @@ impl<'a> From<&'a FunctionCallArgs> for LogFunctionCallArgs<'a> {
 }
 
 fn logged_arguments(args: &Base64VecU8) -> String {
-    let args_len = args.0.len();
+    logged_bytes(&args.0, "argument")
+}
 
-    if args_len > MAX_ARGS_LENGTH {
-        "<argument length is too long>".to_string()
+/// Returns the bytes encoded in base64 or the placeholder with the `name` of the bytes if they
+/// are too long for the log.
+#[must_use]
+pub fn logged_bytes(bytes: &[u8], name: &str) -> String {
+    if bytes.len() > MAX_ARGS_LENGTH {
+        format!("<{name} length is too long>")
     } else {
-        base64::engine::general_purpose::STANDARD.encode(&args.0)
+        base64::engine::general_purpose::STANDARD.encode(bytes)
     }
 }
 
+/// Delegated execution recorded in the history of the controller.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json, borsh])]
+pub struct ExecutionRecord {
+    /// Receiver of the delegated calls.
+    pub receiver_id: AccountId,
+    /// Names of the called methods.
+    pub method_names: Vec<String>,
+    /// Time of the start of the execution.
+    pub started_at: u64,
+    /// Result of the execution.
+    pub status: ExecutionStatus,
+}
+
+/// Result of the delegated execution.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json, borsh])]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionStatus {
+    /// The calls have been started and wait for the result.
+    InProgress,
+    /// The calls have been executed successfully.
+    Succeeded {
+        /// Value returned by the last call encoded in base64. The placeholder is stored instead
+        /// of the value if it's too long.
+        output: String,
+    },
+    /// The execution has failed.
+    Failed,
+    /// The step of the sequential execution plan hasn't been executed because of the failure of
+    /// the previous step.
+    Skipped,
+}
+
+/// Optional parameters of the deployment of the new contract.
+#[derive(Debug, Clone, Default)]
+#[near(serializers = [json])]
+pub struct DeployOptions {
+    /// Policy of the access keys added to the account of the contract. The default keys of
+    /// the controller are added if it isn't provided.
+    #[serde(default)]
+    pub key_policy: Option<KeyPolicy>,
+    /// Account which the contract is deployed to. A new subaccount of the controller is
+    /// created if it isn't provided.
+    #[serde(default)]
+    pub target: Option<DeployTarget>,
+    /// Split of the attached deposit between the balance of the new account and the init call.
+    #[serde(default)]
+    pub deposit_split: Option<DepositSplit>,
+}
+
+/// Split of the attached deposit between the balance of the new account and the init call.
+#[derive(Debug, Clone)]
+#[near(serializers = [json])]
+pub struct DepositSplit {
+    /// Amount transferred to the balance of the new account.
+    pub account_balance: NearToken,
+    /// Amount attached to the init call.
+    pub init_deposit: NearToken,
+    /// Gas attached to the init call. 100 TGas is used if it isn't provided.
+    pub init_gas: Option<Gas>,
+}
+
+/// Account which the new contract is deployed to.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[near(serializers = [json, borsh])]
+#[serde(rename_all = "snake_case")]
+pub enum DeployTarget {
+    /// A new subaccount of the controller.
+    #[default]
+    Subaccount,
+    /// The existing account which contract exposes the upgrade method of its family to
+    /// the controller. The code is deployed by the upgrade method without calling the init
+    /// method, so the attached deposit should be exactly 1 yoctoNEAR.
+    Existing,
+    /// A new account created by the `create_account_advanced` method of the account creator
+    /// contract, e.g. a top-level account created by the linkdrop contract. The attached
+    /// deposit is transferred to the account creator.
+    AccountCreator,
+}
+
+/// Policy of the access keys added to the account of the new contract.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json, borsh])]
+#[serde(rename_all = "snake_case")]
+pub enum KeyPolicy {
+    /// The full access key of the signer of the transaction.
+    Signer,
+    /// No keys, the contract could be upgraded only via its own methods.
+    Locked,
+    /// The provided keys.
+    Keys(Vec<AccessKeyInfo>),
+    /// The default keys of the controller.
+    ControllerDefault,
+}
+
+/// Access key added to the account of the new contract.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json, borsh])]
+pub struct AccessKeyInfo {
+    /// Public key of the access key.
+    pub public_key: PublicKey,
+    /// Permission of the access key.
+    pub permission: KeyPermission,
+}
+
+/// Permission of the access key.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json, borsh])]
+#[serde(rename_all = "snake_case")]
+pub enum KeyPermission {
+    /// Full access to the account.
+    FullAccess,
+    /// Access to the methods of the receiver only.
+    FunctionCall {
+        /// Allowance of the key for paying the gas. The allowance is unlimited if it isn't set.
+        allowance: Option<NearToken>,
+        /// Receiver of the function calls. The new contract is used if it isn't set.
+        receiver_id: Option<AccountId>,
+        /// Allowed methods of the receiver. All methods are allowed if the list is empty.
+        method_names: Vec<String>,
+    },
+}
+
+/// Mismatch between the hash recorded in the deployment info and the actual code hash of
+/// the contract.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json, borsh])]
+pub struct CodeDrift {
+    /// `sha256` hash of the WASM contract recorded in the deployment info.
+    pub expected_hash: String,
+    /// `sha256` hash of the actual code of the contract encoded in hex.
+    pub actual_hash: String,
+    /// Timestamp of the report which has detected the drift.
+    pub detected_at: u64,
+}
+
+/// Deployment of the new contract which has failed after the deposit has been sent.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json, borsh])]
+pub struct FailedDeployment {
+    /// Account which the contract has been deployed to.
+    pub target: DeployTarget,
+    /// Account which has paid for the deployment.
+    pub payer: AccountId,
+    /// `sha256` hash of the WASM contract which has been tried to deploy.
+    pub hash: String,
+    /// Version of the contract which has been tried to deploy.
+    pub version: Version,
+    /// Deposit attached to the deployment.
+    pub deposit: NearToken,
+    /// Part of the deposit which is returned to the controller after the failure and could be
+    /// refunded: the whole deposit for the subaccount, the deposit of the init call for
+    /// the account created by the account creator and nothing for the existing account.
+    pub refundable_deposit: NearToken,
+    /// Account which the deposit has been returned to. The deposit hasn't been returned yet if
+    /// it isn't set.
+    pub refunded_to: Option<AccountId>,
+    /// Time of the failure.
+    pub timestamp: u64,
+}
+
+/// Result of the preflight check of the deployment, upgrade or downgrade.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json])]
+#[serde(rename_all = "snake_case")]
+pub enum Preflight {
+    /// The action passes all checks.
+    Ready(DeploymentPlan),
+    /// The action would fail by the reasons.
+    Rejected { reasons: Vec<String> },
+}
+
+/// Plan of the action on the contract which passed the preflight checks.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json])]
+pub struct DeploymentPlan {
+    /// Action on the contract.
+    pub action: DeploymentAction,
+    /// Account id of the contract.
+    pub contract_id: AccountId,
+    /// Release which the contract is deployed, upgraded or downgraded to.
+    pub release_info: ReleaseInfo,
+    /// Currently deployed version of the contract.
+    pub current_version: Option<Version>,
+    /// Minimal deposit which should be attached to the transaction.
+    pub required_deposit: NearToken,
+    /// Amount of gas attached to the promises created by the transaction.
+    pub attached_gas: Gas,
+}
+
+/// Compatibility rules for upgrading the contracts between versions.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json, borsh])]
+pub struct UpgradePolicy {
+    /// Forbids upgrading to a version with the major part higher than the next one.
+    pub forbid_major_skip: bool,
+    /// Versions of the checkpoint releases which should be deployed before upgrading past them.
+    pub checkpoints: Vec<Version>,
+    /// Forbids upgrading the production deployments to pre-release versions. The deployments are
+    /// marked as production ones by DAO per family or per deployment.
+    pub forbid_pre_release: bool,
+}
+
+impl UpgradePolicy {
+    /// Checks the upgrade from the current version to the target one against the policy.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first violated rule of the policy.
+    pub fn check(
+        &self,
+        current: &Version,
+        target: &Version,
+        is_production: bool,
+    ) -> Result<(), PolicyViolation> {
+        if self.forbid_pre_release && is_production && !target.0.pre.is_empty() {
+            return Err(PolicyViolation::PreRelease {
+                version: target.clone(),
+            });
+        }
+
+        if self.forbid_major_skip && target.0.major > current.0.major.saturating_add(1) {
+            return Err(PolicyViolation::MajorVersionSkip {
+                from: current.clone(),
+                to: target.clone(),
+            });
+        }
+
+        if let Some(checkpoint) = self
+            .checkpoints
+            .iter()
+            .find(|checkpoint| current < *checkpoint && *checkpoint < target)
+        {
+            return Err(PolicyViolation::MissingCheckpoint {
+                checkpoint: checkpoint.clone(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Violated rule of the upgrade policy.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json])]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyViolation {
+    /// The major version of the target release is higher than the next one.
+    MajorVersionSkip { from: Version, to: Version },
+    /// The checkpoint release between the current and the target versions wasn't deployed.
+    MissingCheckpoint { checkpoint: Version },
+    /// The target release is a pre-release version.
+    PreRelease { version: Version },
+}
+
+impl Display for PolicyViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MajorVersionSkip { from, to } => {
+                write!(
+                    f,
+                    "upgrade from version: {from} to: {to} skips major version"
+                )
+            }
+            Self::MissingCheckpoint { checkpoint } => write!(
+                f,
+                "checkpoint release with version: {checkpoint} should be deployed first"
+            ),
+            Self::PreRelease { version } => write!(
+                f,
+                "pre-release version: {version} can't be deployed to the production contract"
+            ),
+        }
+    }
+}
+
+/// Step of the delegated execution plan with the actions executed on the receiver.
+#[derive(Debug, Clone)]
+#[near(serializers = [json])]
+pub struct ExecutionStep {
+    /// Receiver of the actions.
+    pub receiver_id: AccountId,
+    /// Function calls executed on the receiver.
+    pub actions: Vec<FunctionCallArgs>,
+}
+
+/// Order of the execution of the steps of the delegated execution plan.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[near(serializers = [json])]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionMode {
+    /// All steps are executed at once.
+    Parallel,
+    /// The steps are executed one by one in the provided order. The execution stops at
+    /// the first failed step.
+    Sequential,
+}
+
+/// Result of the step of the delegated execution plan.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json])]
+pub struct ExecutionStepResult {
+    /// Id of the execution of the step in the history of the delegated executions.
+    pub execution_id: u64,
+    /// Receiver of the actions of the step.
+    pub receiver_id: AccountId,
+    /// Status of the step.
+    pub status: ExecutionStepStatus,
+    /// Value returned by the last action of the succeeded step encoded in base64.
+    /// The placeholder is stored instead of the value if it's too long.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+}
+
+/// Status of the step of the delegated execution plan.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[near(serializers = [json])]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionStepStatus {
+    /// All actions of the step have been executed successfully.
+    Succeeded,
+    /// The step has failed.
+    Failed,
+    /// The step hasn't been executed because of the failure of the previous step. The deposit
+    /// of its actions is refunded to the caller.
+    Skipped,
+}
+
+/// Policy of the delegated execution of the methods of the deployed contracts by the accounts
+/// with the `Executor` role.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json, borsh])]
+pub struct DelegationPolicy {
+    /// Methods which are allowed to be called.
+    pub allowed_methods: Vec<AllowedMethod>,
+}
+
+impl DelegationPolicy {
+    /// Checks whether the function call is allowed by the policy.
+    ///
+    /// # Errors
+    ///
+    /// Returns the reason if the method isn't allowed or the deposit or the gas exceeds the limit.
+    pub fn check(&self, action: &FunctionCallArgs) -> Result<(), String> {
+        let method_name = &action.function_name;
+        let allowed_method = self
+            .allowed_methods
+            .iter()
+            .find(|method| &method.method_name == method_name)
+            .ok_or_else(|| format!("method: {method_name} isn't allowed"))?;
+
+        if let Some(max_deposit) = allowed_method.max_deposit {
+            if action.amount > max_deposit {
+                return Err(format!(
+                    "deposit: {} of the method: {method_name} exceeds the limit: {}",
+                    action.amount.as_yoctonear(),
+                    max_deposit.as_yoctonear()
+                ));
+            }
+        }
+
+        if let Some(max_gas) = allowed_method.max_gas {
+            if action.gas > max_gas {
+                return Err(format!(
+                    "gas: {} of the method: {method_name} exceeds the limit: {}",
+                    action.gas.as_gas(),
+                    max_gas.as_gas()
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Method which is allowed to be called by the delegated execution.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json, borsh])]
+pub struct AllowedMethod {
+    /// Name of the method.
+    pub method_name: String,
+    /// Maximal deposit attached to the call. The deposit isn't limited if it isn't set.
+    pub max_deposit: Option<NearToken>,
+    /// Maximal gas attached to the call. The gas isn't limited if it isn't set.
+    pub max_gas: Option<Gas>,
+}
+
+/// Methods which the controller is allowed to call for pausing and unpausing the contract.
+/// The first method of the list is called if the name of the method isn't provided.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json, borsh])]
+pub struct PauseMethods {
+    /// Names of the allowed pause methods.
+    pub pause: Vec<String>,
+    /// Names of the allowed unpause methods.
+    pub unpause: Vec<String>,
+    /// Gas attached to the pause method by `pause_all`. 10 TGas is attached if it isn't set.
+    #[serde(default)]
+    pub pause_gas: Option<Gas>,
+    /// Name of the view method which is called by `pause_all` with the pause arguments after
+    /// the pause and returns `true` if the contract is paused, e.g. `pa_is_paused` of
+    /// `near-plugins`. The state of the contract isn't checked if it isn't set.
+    #[serde(default)]
+    pub status_method: Option<String>,
+}
+
+impl Default for PauseMethods {
+    /// Returns the methods of Aurora Engine and the `Pausable` plugin of `near-plugins`.
+    fn default() -> Self {
+        Self {
+            pause: vec!["pause_contract".to_string(), "pa_pause_feature".to_string()],
+            unpause: vec![
+                "resume_contract".to_string(),
+                "pa_unpause_feature".to_string(),
+            ],
+            pause_gas: None,
+            status_method: None,
+        }
+    }
+}
+
+/// Interface used by the controller to upgrade the deployed contract.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json, borsh])]
+#[serde(rename_all = "snake_case")]
+pub enum UpgradeMethod {
+    /// The `upgrade` method of Aurora Engine which accepts the borsh-serialized code and amount
+    /// of gas for the state migration.
+    #[default]
+    Aurora,
+    /// The `up_stage_code` and `up_deploy_code` methods of the `Upgradable` plugin of
+    /// `near-plugins`. The controller should be granted the roles for staging and deploying code.
+    NearPlugins {
+        /// Method which is called after deploying the code with the gas for the state migration.
+        migration_method: Option<String>,
+    },
+    /// Custom method of the contract.
+    Custom {
+        /// Name of the upgrade method.
+        method_name: String,
+        /// Encoding of the upgrade method arguments.
+        encoding: UpgradeArgsEncoding,
+        /// Whether 1 yoctoNEAR should be attached to the call.
+        attach_one_yocto: bool,
+    },
+}
+
+/// Encoding of the arguments of the custom upgrade method.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[near(serializers = [json, borsh])]
+#[serde(rename_all = "snake_case")]
+pub enum UpgradeArgsEncoding {
+    /// Borsh-serialized code and amount of gas for the state migration as in Aurora Engine.
+    Borsh,
+    /// Code of the contract without any encoding.
+    Raw,
+}
+
 #[derive(Debug)]
 #[near(serializers = [borsh])]
 pub struct UpgradeArgs {
@@ -159,6 +1179,10 @@ fn test_logged_arguments() {
 
     let args: Base64VecU8 = vec![1; 1025].into();
     assert_eq!(logged_arguments(&args), "<argument length is too long>");
+    assert_eq!(
+        logged_bytes(&args.0, "output"),
+        "<output length is too long>"
+    );
 
     let action = vec![FunctionCallArgs {
         function_name: "method".to_string(),
@@ -169,3 +1193,117 @@ fn test_logged_arguments() {
     let action_str = near_sdk::serde_json::to_string(&action).unwrap();
     assert!(action_str.len() < 16_384); // 16_384 max size of the log.
 }
+
+#[test]
+fn test_deployment_filter() {
+    let deployment_info = DeploymentInfo {
+        hash: "hash_3_6_4".to_string(),
+        version: "3.6.4".parse().unwrap(),
+        deployment_time: 0,
+        upgrade_times: BTreeMap::new(),
+        init_args: String::new(),
+        last_error: None,
+        channel: None,
+        family: None,
+        key_policy: None,
+        access_keys: vec![],
+    };
+
+    assert!(DeploymentFilter::default().matches(&deployment_info));
+    assert!(DeploymentFilter {
+        min_version: "3.6.4".parse().ok(),
+        max_version: "3.7.0".parse().ok(),
+        hash: Some("hash_3_6_4".to_string()),
+        deployed_after: Some(0),
+        deployed_before: Some(1),
+        family: None,
+    }
+    .matches(&deployment_info));
+    assert!(!DeploymentFilter {
+        deployed_after: Some(1),
+        ..Default::default()
+    }
+    .matches(&deployment_info));
+    assert!(!DeploymentFilter {
+        max_version: "3.6.4".parse().ok(),
+        ..Default::default()
+    }
+    .matches(&deployment_info));
+    assert!(!DeploymentFilter {
+        hash: Some("hash_3_7_0".to_string()),
+        ..Default::default()
+    }
+    .matches(&deployment_info));
+    assert!(!DeploymentFilter {
+        family: Some("bridge".to_string()),
+        ..Default::default()
+    }
+    .matches(&deployment_info));
+}
+
+#[test]
+fn test_release_filter() {
+    let release_info = ReleaseInfo {
+        hash: "hash_3_6_4".to_string(),
+        version: "3.6.4".parse().unwrap(),
+        is_blob_exist: true,
+        downgrade_hash: None,
+        description: None,
+        family: None,
+    };
+
+    assert!(ReleaseFilter::default().matches(&release_info));
+    assert!(ReleaseFilter {
+        min_version: "3.6.0".parse().ok(),
+        max_version: "3.7.0".parse().ok(),
+        is_blob_exist: Some(true),
+    }
+    .matches(&release_info));
+    assert!(!ReleaseFilter {
+        is_blob_exist: Some(false),
+        ..Default::default()
+    }
+    .matches(&release_info));
+}
+
+#[test]
+fn test_upgrade_policy() {
+    let version = |version: &str| version.parse::<Version>().unwrap();
+    let policy = UpgradePolicy {
+        forbid_major_skip: true,
+        checkpoints: vec![version("3.7.0")],
+        forbid_pre_release: true,
+    };
+
+    assert_eq!(
+        policy.check(&version("3.6.4"), &version("3.6.5"), true),
+        Ok(())
+    );
+    assert_eq!(
+        policy.check(&version("3.7.0"), &version("4.1.0"), true),
+        Ok(())
+    );
+    assert_eq!(
+        policy.check(&version("3.6.4"), &version("5.0.0"), true),
+        Err(PolicyViolation::MajorVersionSkip {
+            from: version("3.6.4"),
+            to: version("5.0.0"),
+        })
+    );
+    assert_eq!(
+        policy.check(&version("3.6.4"), &version("3.8.0"), true),
+        Err(PolicyViolation::MissingCheckpoint {
+            checkpoint: version("3.7.0"),
+        })
+    );
+    assert_eq!(
+        policy.check(&version("3.7.0"), &version("3.8.0-rc.1"), true),
+        Err(PolicyViolation::PreRelease {
+            version: version("3.8.0-rc.1"),
+        })
+    );
+    assert_eq!(
+        policy.check(&version("3.7.0"), &version("3.8.0-rc.1"), false),
+        Ok(())
+    );
+}
@@ -4,6 +4,24 @@ pub fn hash_256<T: AsRef<[u8]>>(data: T) -> String {
     hex::encode(hash)
 }
 
+/// Converts the code hash encoded in Base58, e.g. returned by the `view_account` RPC method, into
+/// the hash encoded in hex.
+///
+/// # Errors
+///
+/// Returns an error if the code hash isn't a valid Base58 encoded `sha256` hash.
+pub fn code_hash_to_hex(code_hash: &str) -> Result<String, String> {
+    let bytes = near_sdk::bs58::decode(code_hash)
+        .into_vec()
+        .map_err(|e| format!("bad format of the code hash: {code_hash}: {e}"))?;
+
+    if bytes.len() != 32 {
+        return Err(format!("bad length of the code hash: {code_hash}"));
+    }
+
+    Ok(hex::encode(bytes))
+}
+
 #[test]
 fn test_hash_256() {
     const HELLO_HASH: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
@@ -11,3 +29,13 @@ fn test_hash_256() {
     assert_eq!(hash_256(b"hello"), HELLO_HASH);
     assert_ne!(hash_256(b"hell0"), HELLO_HASH);
 }
+
+#[test]
+fn test_code_hash_to_hex() {
+    let hash = near_sdk::env::sha256_array(b"hello");
+    let code_hash = near_sdk::bs58::encode(hash).into_string();
+
+    assert_eq!(code_hash_to_hex(&code_hash).unwrap(), hash_256(b"hello"));
+    assert!(code_hash_to_hex("0OIl").is_err());
+    assert!(code_hash_to_hex("11111111").is_err());
+}